@@ -1,8 +1,26 @@
 pub mod general {
     pub const BPS: u16 = 10_000;
     pub const MAX_ALLOWED_FEE_IN_BPS: u64 = 500;
+    pub const MAX_CREATOR_ALLOCATION_IN_BPS: u64 = 2_000;
+    pub const MAX_FEE_TIERS: usize = 8;
+    pub const MAX_TRANSFER_FEE_IN_BPS: u16 = 1_000;
+    pub const MAX_DESCRIPTION_LEN: usize = 256;
+    pub const MAX_LINK_LEN: usize = 100;
+    /// Longest name or symbol a queued reservation holds, the metadata name limit
+    pub const MAX_RESERVATION_LEN: usize = 32;
+    pub const MAX_TIMELOCK_DELAY: i64 = 30 * 24 * 60 * 60;
+    pub const BATCH_BUY_ACCOUNTS: usize = 5;
+    pub const MAX_PRICE_OBSERVATIONS: usize = 32;
     pub const DECIMALS: u8 = 9;
     pub const DISCRIMINATOR_SIZE: usize = 8;
+    /// Id of the legacy singleton platform, derived without an id seed
+    pub const LEGACY_PLATFORM_ID: u64 = 0;
+    /// Layout version of `Platform`. Every field up to `fee_vault_bump` ships in one release
+    /// as version 1, any later layout change must bump it and add a migration path
+    pub const PLATFORM_VERSION: u8 = 1;
+    /// Layout version of `TokenInfo`. Every field up to `max_holding_bps` ships in one release
+    /// as version 1, any later layout change must bump it and add a migration path
+    pub const TOKEN_INFO_VERSION: u8 = 1;
 }
 
 pub mod seeds {
@@ -10,4 +28,17 @@ pub mod seeds {
     pub const MINT_SEED: &[u8] = b"mint";
     pub const TOKEN_SEED: &[u8] = b"token";
     pub const TOKEN_ACCOUNT_SEED: &[u8] = b"token_account";
+    pub const VESTING_SEED: &[u8] = b"vesting";
+    pub const VESTING_ACCOUNT_SEED: &[u8] = b"vesting_account";
+    pub const REFERRAL_SEED: &[u8] = b"referral";
+    pub const PENDING_CHANGE_SEED: &[u8] = b"pending_change";
+    pub const ALLOWED_CREATOR_SEED: &[u8] = b"allowed_creator";
+    pub const QUOTE_VAULT_SEED: &[u8] = b"quote_vault";
+    pub const FEE_VAULT_SEED: &[u8] = b"fee_vault";
+    pub const METADATA_SEED: &[u8] = b"metadata";
+    pub const COLLECTION_SEED: &[u8] = b"collection";
+    pub const COLLECTION_ACCOUNT_SEED: &[u8] = b"collection_account";
+    pub const TOKEN_PROFILE_SEED: &[u8] = b"token_profile";
+    pub const RESERVATION_SEED: &[u8] = b"reservation";
+    pub const PRICE_OBSERVATIONS_SEED: &[u8] = b"price_observations";
 }
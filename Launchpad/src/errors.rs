@@ -12,4 +12,104 @@ pub enum CustomErrors {
     AlreadyLaunched,
     #[msg("Not launched")]
     NotLaunched,
+    #[msg("Creator allocation in bips should not exceed 2000")]
+    ExcessiveCreatorAllocation,
+    #[msg("Invalid vesting schedule")]
+    InvalidVestingSchedule,
+    #[msg("Vesting accounts missing")]
+    VestingAccountsMissing,
+    #[msg("Vesting accounts should be omitted without a creator allocation")]
+    UnexpectedVestingAccounts,
+    #[msg("Not vesting beneficiary")]
+    NotVestingBeneficiary,
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+    #[msg("Referral fee in bips should not exceed 10000")]
+    ExcessiveReferralFees,
+    #[msg("Cannot refer yourself")]
+    SelfReferral,
+    #[msg("Not referrer")]
+    NotReferrer,
+    #[msg("Invalid fee schedule")]
+    InvalidFeeSchedule,
+    #[msg("Owner should not be the default pubkey")]
+    InvalidOwner,
+    #[msg("Total supply should be greater than zero")]
+    ZeroTotalSupply,
+    #[msg("Virtual sol amount should be greater than zero")]
+    ZeroVirtualSol,
+    #[msg("Target pool balance should exceed virtual sol amount")]
+    TargetPoolBalanceBelowVirtualSol,
+    #[msg("Timelock delay should not exceed 30 days")]
+    InvalidTimelockDelay,
+    #[msg("Config changes must be queued while the timelock is active")]
+    TimelockActive,
+    #[msg("Timelock not mature")]
+    TimelockNotMature,
+    #[msg("Invalid treasury")]
+    InvalidTreasury,
+    #[msg("Amount exceeds accumulated fees")]
+    InsufficientFees,
+    #[msg("Withdrawal would leave the account below the rent exempt minimum")]
+    RentExemptionBreached,
+    #[msg("Token halted")]
+    TokenHalted,
+    #[msg("Invalid account layout")]
+    InvalidAccountLayout,
+    #[msg("Already migrated")]
+    AlreadyMigrated,
+    #[msg("Token belongs to another platform")]
+    PlatformMismatch,
+    #[msg("Platform id 0 is reserved for the legacy platform")]
+    InvalidPlatformId,
+    #[msg("Creator not allowed to launch on this platform")]
+    CreatorNotAllowed,
+    #[msg("Quote token accounts missing")]
+    QuoteAccountsMissing,
+    #[msg("Referrals are only supported on sol quoted tokens")]
+    ReferralRequiresNativeQuote,
+    #[msg("Creation fees are only supported on sol quoted platforms")]
+    CreationFeeRequiresNativeQuote,
+    #[msg("Transfer fee exceeds the maximum allowed")]
+    ExcessiveTransferFee,
+    #[msg("Mint or freeze authority not revoked")]
+    AuthorityNotRevoked,
+    #[msg("Only the token creator can perform this action")]
+    NotCreator,
+    #[msg("Token metadata is locked")]
+    MetadataLocked,
+    #[msg("Token metadata is not managed by the program")]
+    MetadataNotProgramManaged,
+    #[msg("Metadata authority not supported for this token program")]
+    UnsupportedMetadataAuthority,
+    #[msg("Platform collection already created")]
+    CollectionAlreadyCreated,
+    #[msg("Collection accounts missing")]
+    CollectionAccountsMissing,
+    #[msg("Token profile account and params must be passed together")]
+    TokenProfileMismatch,
+    #[msg("Token profile field too long")]
+    TokenProfileFieldTooLong,
+    #[msg("Token name or symbol is reserved")]
+    NameReserved,
+    #[msg("Token info does not belong to this mint")]
+    TokenMismatch,
+    #[msg("Received fewer tokens than the minimum amount out")]
+    SlippageExceeded,
+    #[msg("Only supported on sol quoted tokens")]
+    NativeQuoteRequired,
+    #[msg("Cannot swap a token for itself")]
+    SameToken,
+    #[msg("Invalid remaining accounts")]
+    InvalidRemainingAccounts,
+    #[msg("Max holding in bips should be between 1 and 10000")]
+    InvalidMaxHolding,
+    #[msg("Buy exceeds the max holding per wallet")]
+    MaxHoldingExceeded,
+    #[msg("Sol reserve is below the virtual sol")]
+    ReserveBelowVirtualSol,
+    #[msg("Reservation value too long to queue")]
+    ReservationTooLong,
+    #[msg("Accounts do not match the pending operation")]
+    OperationAccountsMismatch,
 }
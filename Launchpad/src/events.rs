@@ -1,9 +1,13 @@
 use anchor_lang::prelude::*;
 
+use crate::{FeeSchedule, LaunchMode, PendingOperation, PlatformConfig};
+
 #[event]
 pub struct PlatformInitialized {
     pub platform: Pubkey,
+    pub id: u64,
     pub owner: Pubkey,
+    pub quote_mint: Pubkey,
 }
 
 #[event]
@@ -11,11 +15,62 @@ pub struct OwnerChanged {
     pub new_owner: Pubkey,
 }
 
+#[event]
+pub struct TreasuryChanged {
+    pub new_treasury: Pubkey,
+}
+
+#[event]
+pub struct LaunchModeChanged {
+    pub new_launch_mode: LaunchMode,
+}
+
+#[event]
+pub struct ReservationAdded {
+    pub platform: Pubkey,
+    pub hash: [u8; 32],
+    pub value: String,
+    pub creator: Option<Pubkey>,
+}
+
+#[event]
+pub struct ReservationRemoved {
+    pub platform: Pubkey,
+    pub hash: [u8; 32],
+}
+
+#[event]
+pub struct CreatorAllowed {
+    pub platform: Pubkey,
+    pub creator: Pubkey,
+}
+
+#[event]
+pub struct CreatorDisallowed {
+    pub platform: Pubkey,
+    pub creator: Pubkey,
+}
+
 #[event]
 pub struct FeesChanged {
     pub new_fees: u64,
 }
 
+#[event]
+pub struct FeeScheduleChanged {
+    pub new_fee_schedule: FeeSchedule,
+}
+
+#[event]
+pub struct ReferralFeesChanged {
+    pub new_referral_fees: u64,
+}
+
+#[event]
+pub struct CreationFeeChanged {
+    pub new_creation_fee: u64,
+}
+
 #[event]
 pub struct TotalSupplyChanged {
     pub new_total_supply: u64,
@@ -31,14 +86,160 @@ pub struct TargetPoolBalanceChanged {
     pub new_target_pool_balance: u64,
 }
 
+#[event]
+pub struct ConfigUpdated {
+    pub config_version: u64,
+    pub old_config: PlatformConfig,
+    pub new_config: PlatformConfig,
+}
+
+#[event]
+pub struct ConfigChangeQueued {
+    pub id: u64,
+    pub eta: i64,
+    pub operation: PendingOperation,
+}
+
+#[event]
+pub struct ConfigChangeExecuted {
+    pub id: u64,
+    pub config_version: u64,
+    pub old_config: PlatformConfig,
+    pub new_config: PlatformConfig,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub id: u64,
+}
+
 #[event]
 pub struct FeesWithdrawn {
+    pub treasury: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PlatformAudited {
+    pub platform: Pubkey,
+    pub expected_lamports: u64,
+    pub actual_lamports: u64,
+    pub shortfall: u64,
+    pub surplus: u64,
+}
+
+#[event]
+pub struct TokenAudited {
+    pub token: Pubkey,
+    pub expected_lamports: u64,
+    pub actual_lamports: u64,
+    pub shortfall: u64,
+    pub surplus: u64,
+    pub halted: bool,
+}
+
+#[event]
+pub struct QuoteVaultAudited {
+    pub vault: Pubkey,
+    pub expected_amount: u64,
+    pub actual_amount: u64,
+    pub shortfall: u64,
+    pub surplus: u64,
+}
+
+#[event]
+pub struct TokenResumed {
+    pub token: Pubkey,
+}
+
+#[event]
+pub struct PlatformMigrated {
+    pub platform: Pubkey,
+    pub version: u8,
+}
+
+#[event]
+pub struct TokenInfoMigrated {
+    pub token: Pubkey,
+    pub version: u8,
+}
+
+#[event]
+pub struct ReferrerRegistered {
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct ReferralFeePaid {
+    pub referrer: Pubkey,
+    pub token: Pubkey,
+    pub trader: Pubkey,
+    pub volume: u64,
     pub amount: u64,
 }
 
+#[event]
+pub struct ReferralFeesClaimed {
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PlatformCollectionCreated {
+    pub platform: Pubkey,
+    pub collection_mint: Pubkey,
+}
+
 #[event]
 pub struct TokenCreated {
+    pub platform: Pubkey,
+    pub token: Pubkey,
+    pub quote_mint: Pubkey,
+    pub creation_fee: u64,
+    pub mint_authority: Option<Pubkey>,
+    pub freeze_authority: Option<Pubkey>,
+    pub metadata_update_authority: Option<Pubkey>,
+}
+
+#[event]
+pub struct CreatorAllocationVested {
+    pub token: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub cliff_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct TokenProfileUpdated {
+    pub token: Pubkey,
+    pub content_hash: [u8; 32],
+}
+
+#[event]
+pub struct TokenMetadataUpdated {
+    pub token: Pubkey,
+    pub symbol: String,
+    pub uri: String,
+}
+
+#[event]
+pub struct MetadataLocked {
+    pub token: Pubkey,
+}
+
+#[event]
+pub struct VestedTokensClaimed {
     pub token: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TokensBought {
+    pub token: Pubkey,
+    pub by: Pubkey,
+    pub amount: u64,
 }
 
 #[event]
@@ -48,6 +249,22 @@ pub struct TokensSold {
     pub amount: u64,
 }
 
+#[event]
+pub struct PriceObserved {
+    pub token: Pubkey,
+    pub timestamp: i64,
+    pub price_cumulative: u128,
+}
+
+#[event]
+pub struct TokensSwapped {
+    pub from_token: Pubkey,
+    pub to_token: Pubkey,
+    pub by: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
 #[event]
 pub struct LiquidityAdded {
     pub token: Pubkey,
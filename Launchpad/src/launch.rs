@@ -0,0 +1,207 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{
+    self, spl_token_2022::instruction::AuthorityType, Mint as MintInterface, MintTo, SetAuthority,
+};
+
+use crate::{
+    errors, events, utils, CreateTokenParams, Platform, PriceObservations, Reservation, TokenInfo,
+    TokenInfoBumps, TokenProfile, Vesting,
+};
+
+/// Fails unless `creator` may launch a token with `create_token_params` on `platform`
+pub fn check_launch(
+    platform: &Platform,
+    creator: &Pubkey,
+    allowlisted: bool,
+    name_reservation: &AccountInfo,
+    symbol_reservation: &AccountInfo,
+    create_token_params: &CreateTokenParams,
+) -> Result<()> {
+    create_token_params.validate()?;
+
+    require!(
+        platform.is_creator_allowed(creator, allowlisted),
+        errors::CustomErrors::CreatorNotAllowed
+    );
+
+    // Reserved names and symbols can only be launched by the creator they are assigned to
+    Reservation::check_available(name_reservation, creator)?;
+    Reservation::check_available(symbol_reservation, creator)?;
+
+    Ok(())
+}
+
+/// Collects the creation fee from `payer` unless it is waived for allowlisted creators,
+/// returns the fee charged
+pub fn collect_creation_fee<'info>(
+    platform: &mut Account<'info, Platform>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    allowlisted: bool,
+) -> Result<u64> {
+    let creation_fee = platform.creation_fee_for(allowlisted);
+    if creation_fee > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: platform.to_account_info(),
+                },
+            ),
+            creation_fee,
+        )?;
+        platform.accumulated_fees += creation_fee;
+    }
+
+    Ok(creation_fee)
+}
+
+/// Initializes the campaign of a new token along with its price observations and optional
+/// profile, returns the creator allocation carved out of the total supply
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_campaign(
+    platform: &Account<Platform>,
+    token_info: &mut TokenInfo,
+    price_observations: &mut PriceObservations,
+    token_profile: Option<&mut TokenProfile>,
+    mint: Pubkey,
+    creator: Pubkey,
+    create_token_params: &CreateTokenParams,
+    bumps: TokenInfoBumps,
+    price_observations_bump: u8,
+    has_quote_vault: bool,
+) -> Result<u64> {
+    // The creator allocation never enters the curve
+    let creator_allocation = utils::calculate_allocation(
+        &(platform.total_supply as u128),
+        &(create_token_params.creator_allocation_bps as u128),
+    );
+
+    token_info.initialize(
+        platform,
+        mint,
+        creator,
+        creator_allocation,
+        Clock::get()?.unix_timestamp,
+        bumps,
+    );
+
+    // The curve reserve of tokens quoted in an spl token is held by their quote vault
+    require!(
+        token_info.is_native_quote() || has_quote_vault,
+        errors::CustomErrors::QuoteAccountsMissing
+    );
+
+    token_info.max_holding_bps = create_token_params.max_holding_bps;
+    price_observations.initialize(token_info, price_observations_bump);
+
+    // Optionally, publish the token profile next to the campaign
+    match (token_profile, create_token_params.profile.as_ref()) {
+        (Some(token_profile), Some(token_profile_params)) => {
+            token_profile.token = token_info.token;
+            token_profile.apply(token_profile_params)?;
+
+            let token_profile_updated_event = events::TokenProfileUpdated {
+                token: token_profile.token,
+                content_hash: token_profile.content_hash,
+            };
+            emit!(token_profile_updated_event);
+        }
+        (None, None) => {}
+        _ => return err!(errors::CustomErrors::TokenProfileMismatch),
+    }
+
+    Ok(creator_allocation)
+}
+
+/// Locks the creator allocation in the vesting escrow of `beneficiary`,
+/// minted by the mint PDA signing with `mint_signer`
+#[allow(clippy::too_many_arguments)]
+pub fn lock_creator_allocation<'info>(
+    vesting: &mut Vesting,
+    vesting_token_account: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    mint_signer: &[&[&[u8]]],
+    beneficiary: Pubkey,
+    creator_allocation: u64,
+    create_token_params: &CreateTokenParams,
+) -> Result<()> {
+    vesting.initialize(
+        mint.key(),
+        beneficiary,
+        creator_allocation,
+        create_token_params,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            MintTo {
+                authority: mint.clone(),
+                to: vesting_token_account.clone(),
+                mint: mint.clone(),
+            },
+            mint_signer,
+        ),
+        creator_allocation,
+    )?;
+
+    let creator_allocation_vested_event = events::CreatorAllocationVested {
+        token: vesting.token,
+        beneficiary: vesting.beneficiary,
+        amount: vesting.total_amount,
+        cliff_time: vesting.cliff_time,
+        end_time: vesting.end_time,
+    };
+    emit!(creator_allocation_vested_event);
+
+    Ok(())
+}
+
+/// Mints the curve supply to the token vault, then revokes the mint authority so the supply
+/// can never be inflated. Returns the mint once its authorities are checked to be revoked
+pub fn mint_curve_supply<'info>(
+    mint: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    mint_signer: &[&[&[u8]]],
+    token_reserve: u64,
+) -> Result<MintInterface> {
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            MintTo {
+                authority: mint.clone(),
+                to: token_account.clone(),
+                mint: mint.clone(),
+            },
+            mint_signer,
+        ),
+        token_reserve,
+    )?;
+
+    token_interface::set_authority(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            SetAuthority {
+                current_authority: mint.clone(),
+                account_or_mint: mint.clone(),
+            },
+            mint_signer,
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    let mint = MintInterface::try_deserialize(&mut &mint.try_borrow_data()?[..])?;
+    require!(
+        mint.mint_authority.is_none() && mint.freeze_authority.is_none(),
+        errors::CustomErrors::AuthorityNotRevoked
+    );
+
+    Ok(mint)
+}
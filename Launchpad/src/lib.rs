@@ -1,19 +1,35 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
+use anchor_lang::Discriminator;
 use anchor_spl::{
-    associated_token::AssociatedToken,
+    associated_token::{self, get_associated_token_address_with_program_id, AssociatedToken},
     metadata::{
-        create_metadata_accounts_v3, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3,
-        Metadata as Metaplex,
+        create_master_edition_v3, create_metadata_accounts_v3,
+        mpl_token_metadata::types::{Collection, CollectionDetails, Creator, DataV2},
+        sign_metadata, update_metadata_accounts_v2, verify_sized_collection_item,
+        CreateMasterEditionV3, CreateMetadataAccountsV3, Metadata as Metaplex, MetadataAccount,
+        SignMetadata, UpdateMetadataAccountsV2, VerifySizedCollectionItem,
+    },
+    token::{mint_to, Mint, MintTo, Token, TokenAccount},
+    token_2022::Token2022,
+    token_interface::{
+        self, Mint as MintInterface, TokenAccount as TokenAccountInterface, TokenInterface,
+        TransferChecked,
     },
-    token::{self, mint_to, Mint, MintTo, Token, TokenAccount, Transfer as SplTransfer},
 };
 
 mod constants;
 mod errors;
 mod events;
+mod launch;
+mod migrations;
+mod token_extensions;
 mod utils;
 
+use constants::general::{
+    MAX_DESCRIPTION_LEN, MAX_FEE_TIERS, MAX_LINK_LEN, MAX_PRICE_OBSERVATIONS, MAX_RESERVATION_LEN,
+};
+
 declare_id!("FtxVHduAJmYGWTFRAWpUvtpkphK42EBvqezQ9HGcMHyp");
 
 #[program]
@@ -21,30 +37,58 @@ pub mod ZkAGI_Contracts {
     use super::*;
 
     pub fn initialize(ctx: Context<Initialize>, init_params: PlatformInitParams) -> Result<()> {
+        // The legacy singleton platform is the only one without an id
         require!(
-            init_params.fee_in_bps <= constants::general::MAX_ALLOWED_FEE_IN_BPS,
-            errors::CustomErrors::ExcessiveFees
+            init_params.id != constants::general::LEGACY_PLATFORM_ID,
+            errors::CustomErrors::InvalidPlatformId
         );
 
         let platform = &mut ctx.accounts.platform;
 
+        platform.version = constants::general::PLATFORM_VERSION;
+        platform.id = init_params.id;
         platform.fee_in_bps = init_params.fee_in_bps;
+        platform.fee_schedule = FeeSchedule::default();
+        platform.referral_fee_in_bps = init_params.referral_fee_in_bps;
         platform.owner = init_params.owner;
+        platform.treasury = init_params.treasury;
         platform.total_supply = init_params.total_supply;
         platform.virtual_sol = init_params.virtual_sol;
         platform.target_pool_balance = init_params.target_pool_balance;
+        platform.timelock_delay = init_params.timelock_delay;
+        platform.launch_mode = init_params.launch_mode;
+        platform.creation_fee = init_params.creation_fee;
+        platform.creation_fee_waived_for_allowlisted =
+            init_params.creation_fee_waived_for_allowlisted;
+        platform.bump = ctx.bumps.platform;
+        platform.fee_vault_bump = ctx.bumps.fee_vault.unwrap_or_default();
+
+        // Platforms quoted in an spl token accrue their trading fees in a fee vault
+        if let Some(quote_mint) = &ctx.accounts.quote_mint {
+            require!(
+                ctx.accounts.fee_vault.is_some(),
+                errors::CustomErrors::QuoteAccountsMissing
+            );
+            platform.quote_mint = quote_mint.key();
+        }
+        platform.validate()?;
 
         let platform_initialized_event = events::PlatformInitialized {
             platform: ctx.accounts.platform.key(),
+            id: init_params.id,
             owner: ctx.accounts.signer.key(),
+            quote_mint: ctx.accounts.platform.quote_mint,
         };
         emit!(platform_initialized_event);
 
         Ok(())
     }
 
-    pub fn change_owner(ctx: Context<PlatformOperation>, new_owner: Pubkey) -> Result<()> {
+    /// Only applies immediately without a timelock, timelocked platforms queue an owner change
+    pub fn change_owner(ctx: Context<ConfigOperation>, new_owner: Pubkey) -> Result<()> {
         ctx.accounts.platform.owner = new_owner;
+        ctx.accounts.platform.validate()?;
+        ctx.accounts.platform.config_version += 1;
 
         let owner_changed_event = events::OwnerChanged { new_owner };
         emit!(owner_changed_event);
@@ -52,8 +96,93 @@ pub mod ZkAGI_Contracts {
         Ok(())
     }
 
-    pub fn change_fees(ctx: Context<PlatformOperation>, new_fees: u64) -> Result<()> {
+    /// Only applies immediately without a timelock, timelocked platforms queue a treasury change
+    pub fn change_treasury(ctx: Context<ConfigOperation>, new_treasury: Pubkey) -> Result<()> {
+        ctx.accounts.platform.treasury = new_treasury;
+        ctx.accounts.platform.validate()?;
+        ctx.accounts.platform.config_version += 1;
+
+        let treasury_changed_event = events::TreasuryChanged { new_treasury };
+        emit!(treasury_changed_event);
+
+        Ok(())
+    }
+
+    /// Only applies immediately without a timelock, timelocked platforms queue a launch mode change
+    pub fn change_launch_mode(
+        ctx: Context<ConfigOperation>,
+        new_launch_mode: LaunchMode,
+    ) -> Result<()> {
+        ctx.accounts.platform.launch_mode = new_launch_mode;
+        ctx.accounts.platform.config_version += 1;
+
+        let launch_mode_changed_event = events::LaunchModeChanged { new_launch_mode };
+        emit!(launch_mode_changed_event);
+
+        Ok(())
+    }
+
+    pub fn add_allowed_creator(ctx: Context<AddAllowedCreator>, creator: Pubkey) -> Result<()> {
+        let allowed_creator = &mut ctx.accounts.allowed_creator;
+        allowed_creator.platform = ctx.accounts.platform.key();
+        allowed_creator.creator = creator;
+
+        let creator_allowed_event = events::CreatorAllowed {
+            platform: allowed_creator.platform,
+            creator,
+        };
+        emit!(creator_allowed_event);
+
+        Ok(())
+    }
+
+    /// Only applies immediately without a timelock, timelocked platforms queue the reservation
+    pub fn add_reservation(
+        ctx: Context<AddReservation>,
+        value: String,
+        creator: Option<Pubkey>,
+    ) -> Result<()> {
+        let reservation = &mut ctx.accounts.reservation;
+        reservation.platform = ctx.accounts.platform.key();
+        reservation.hash = utils::get_reservation_hash(&value);
+        reservation.creator = creator;
+
+        let reservation_added_event = events::ReservationAdded {
+            platform: reservation.platform,
+            hash: reservation.hash,
+            value,
+            creator,
+        };
+        emit!(reservation_added_event);
+
+        Ok(())
+    }
+
+    /// Only applies immediately without a timelock, timelocked platforms queue the removal
+    pub fn remove_reservation(ctx: Context<RemoveReservation>) -> Result<()> {
+        let reservation_removed_event = events::ReservationRemoved {
+            platform: ctx.accounts.platform.key(),
+            hash: ctx.accounts.reservation.hash,
+        };
+        emit!(reservation_removed_event);
+
+        Ok(())
+    }
+
+    pub fn remove_allowed_creator(ctx: Context<RemoveAllowedCreator>) -> Result<()> {
+        let creator_disallowed_event = events::CreatorDisallowed {
+            platform: ctx.accounts.platform.key(),
+            creator: ctx.accounts.allowed_creator.creator,
+        };
+        emit!(creator_disallowed_event);
+
+        Ok(())
+    }
+
+    pub fn change_fees(ctx: Context<ConfigOperation>, new_fees: u64) -> Result<()> {
         ctx.accounts.platform.fee_in_bps = new_fees;
+        ctx.accounts.platform.validate()?;
+        ctx.accounts.platform.config_version += 1;
 
         let fees_changed_event = events::FeesChanged { new_fees };
         emit!(fees_changed_event);
@@ -61,11 +190,49 @@ pub mod ZkAGI_Contracts {
         Ok(())
     }
 
-    pub fn change_total_supply(
-        ctx: Context<PlatformOperation>,
-        new_total_supply: u64,
+    pub fn change_fee_schedule(
+        ctx: Context<ConfigOperation>,
+        new_fee_schedule: FeeSchedule,
     ) -> Result<()> {
+        ctx.accounts.platform.fee_schedule = new_fee_schedule.clone();
+        ctx.accounts.platform.validate()?;
+        ctx.accounts.platform.config_version += 1;
+
+        let fee_schedule_changed_event = events::FeeScheduleChanged { new_fee_schedule };
+        emit!(fee_schedule_changed_event);
+
+        Ok(())
+    }
+
+    pub fn change_referral_fees(
+        ctx: Context<ConfigOperation>,
+        new_referral_fees: u64,
+    ) -> Result<()> {
+        ctx.accounts.platform.referral_fee_in_bps = new_referral_fees;
+        ctx.accounts.platform.validate()?;
+        ctx.accounts.platform.config_version += 1;
+
+        let referral_fees_changed_event = events::ReferralFeesChanged { new_referral_fees };
+        emit!(referral_fees_changed_event);
+
+        Ok(())
+    }
+
+    pub fn change_creation_fee(ctx: Context<ConfigOperation>, new_creation_fee: u64) -> Result<()> {
+        ctx.accounts.platform.creation_fee = new_creation_fee;
+        ctx.accounts.platform.validate()?;
+        ctx.accounts.platform.config_version += 1;
+
+        let creation_fee_changed_event = events::CreationFeeChanged { new_creation_fee };
+        emit!(creation_fee_changed_event);
+
+        Ok(())
+    }
+
+    pub fn change_total_supply(ctx: Context<ConfigOperation>, new_total_supply: u64) -> Result<()> {
         ctx.accounts.platform.total_supply = new_total_supply;
+        ctx.accounts.platform.validate()?;
+        ctx.accounts.platform.config_version += 1;
 
         let total_supply_changed_event = events::TotalSupplyChanged { new_total_supply };
         emit!(total_supply_changed_event);
@@ -74,10 +241,12 @@ pub mod ZkAGI_Contracts {
     }
 
     pub fn change_virtual_sol_amount(
-        ctx: Context<PlatformOperation>,
+        ctx: Context<ConfigOperation>,
         new_virtual_sol_amount: u64,
     ) -> Result<()> {
         ctx.accounts.platform.virtual_sol = new_virtual_sol_amount;
+        ctx.accounts.platform.validate()?;
+        ctx.accounts.platform.config_version += 1;
 
         let virtual_sol_amount_changed_event = events::VirtualSolChanged {
             new_virtual_sol_amount,
@@ -88,10 +257,12 @@ pub mod ZkAGI_Contracts {
     }
 
     pub fn change_target_pool_balance(
-        ctx: Context<PlatformOperation>,
+        ctx: Context<ConfigOperation>,
         new_target_pool_balance: u64,
     ) -> Result<()> {
         ctx.accounts.platform.target_pool_balance = new_target_pool_balance;
+        ctx.accounts.platform.validate()?;
+        ctx.accounts.platform.config_version += 1;
 
         let target_pool_balance_changed_event = events::TargetPoolBalanceChanged {
             new_target_pool_balance,
@@ -101,297 +272,1990 @@ pub mod ZkAGI_Contracts {
         Ok(())
     }
 
-    pub fn withdraw_fees(ctx: Context<WithdrawFees>) -> Result<()> {
+    pub fn update_config(
+        ctx: Context<ConfigOperation>,
+        update_config_params: UpdateConfigParams,
+    ) -> Result<()> {
         let platform = &mut ctx.accounts.platform;
-        let accumulated_fees = platform.accumulated_fees;
-
-        **platform.to_account_info().try_borrow_mut_lamports()? -= accumulated_fees;
-        **ctx
-            .accounts
-            .signer
-            .to_account_info()
-            .try_borrow_mut_lamports()? += accumulated_fees;
+        let old_config = platform.config();
 
-        platform.accumulated_fees = 0;
+        platform.apply_config(update_config_params);
+        platform.validate()?;
+        platform.config_version += 1;
 
-        let fees_withdrawn_event = events::FeesWithdrawn {
-            amount: accumulated_fees,
+        let config_updated_event = events::ConfigUpdated {
+            config_version: platform.config_version,
+            old_config,
+            new_config: platform.config(),
         };
-        emit!(fees_withdrawn_event);
+        emit!(config_updated_event);
 
         Ok(())
     }
 
-    pub fn create_token(
-        ctx: Context<CreateToken>,
-        create_token_params: CreateTokenParams,
+    pub fn queue_config_change(
+        ctx: Context<QueueConfigChange>,
+        update_config_params: UpdateConfigParams,
     ) -> Result<()> {
-        let seeds = &[
-            constants::seeds::MINT_SEED,
-            create_token_params.name.as_bytes(),
-            &[ctx.bumps.mint],
-        ];
-        let signer = [&seeds[..]];
-
-        // First, create the token metadata
-        // The token was already created by Anchor in the background
-        let token_data: DataV2 = DataV2 {
-            name: create_token_params.name.clone(),
-            symbol: create_token_params.symbol,
-            uri: create_token_params.uri,
-            seller_fee_basis_points: 0,
-            creators: None,
-            collection: None,
-            uses: None,
-        };
-        let metadata_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_metadata_program.to_account_info(),
-            CreateMetadataAccountsV3 {
-                payer: ctx.accounts.signer.to_account_info(),
-                update_authority: ctx.accounts.mint.to_account_info(),
-                mint: ctx.accounts.mint.to_account_info(),
-                metadata: ctx.accounts.metadata.to_account_info(),
-                mint_authority: ctx.accounts.mint.to_account_info(),
-                system_program: ctx.accounts.system_program.to_account_info(),
-                rent: ctx.accounts.rent.to_account_info(),
+        queue_operation(
+            ctx,
+            PendingOperation::UpdateConfig {
+                params: update_config_params,
             },
-            &signer,
-        );
-        create_metadata_accounts_v3(metadata_ctx, token_data, false, true, None)?;
+        )
+    }
 
-        // Second, initialize the token campaign params
-        let token_info = &mut ctx.accounts.token_info;
-        token_info.token = ctx.accounts.mint.key();
-        token_info.total_supply = ctx.accounts.platform.total_supply;
-        token_info.virtual_sol = ctx.accounts.platform.virtual_sol;
-        token_info.sol_reserve = token_info.virtual_sol;
-        token_info.token_reserve = token_info.total_supply;
-        token_info.target_pool_balance = ctx.accounts.platform.target_pool_balance;
-
-        // Lastly, mint the total supply of the tokens to the token vault associated with this token launch
-        mint_to(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    authority: ctx.accounts.mint.to_account_info(),
-                    to: ctx.accounts.token_account.to_account_info(),
-                    mint: ctx.accounts.mint.to_account_info(),
-                },
-                &signer,
+    /// Queues any owner operation behind the timelock, config updates included
+    pub fn queue_operation(
+        ctx: Context<QueueConfigChange>,
+        operation: PendingOperation,
+    ) -> Result<()> {
+        // Reject invalid changes now rather than once the delay has elapsed
+        match &operation {
+            PendingOperation::UpdateConfig { params } => {
+                let mut preview = Platform::clone(&ctx.accounts.platform);
+                preview.apply_config(params.clone());
+                preview.validate()?;
+            }
+            PendingOperation::AddReservation { value, .. } => require!(
+                value.len() <= MAX_RESERVATION_LEN,
+                errors::CustomErrors::ReservationTooLong
             ),
-            ctx.accounts.token_info.total_supply,
-        )?;
+            PendingOperation::RemoveReservation { .. } | PendingOperation::ResumeToken { .. } => {}
+        }
 
-        let token_created_event = events::TokenCreated {
-            token: ctx.accounts.mint.key(),
+        let platform = &mut ctx.accounts.platform;
+        let pending_change = &mut ctx.accounts.pending_change;
+
+        pending_change.platform = platform.key();
+        pending_change.id = platform.pending_change_nonce;
+        pending_change.proposer = ctx.accounts.signer.key();
+        pending_change.operation = operation.clone();
+        pending_change.eta = Clock::get()?.unix_timestamp + platform.timelock_delay;
+
+        platform.pending_change_nonce += 1;
+
+        let config_change_queued_event = events::ConfigChangeQueued {
+            id: pending_change.id,
+            eta: pending_change.eta,
+            operation,
         };
-        emit!(token_created_event);
+        emit!(config_change_queued_event);
 
         Ok(())
     }
 
-    pub fn init_token_account_for_token(_: Context<InitAccountsForToken>) -> Result<()> {
-        msg!("Token account created");
+    /// Only the accounts the queued operation acts on are passed, the others are omitted
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        let pending_change = &ctx.accounts.pending_change;
+        require!(
+            Clock::get()?.unix_timestamp >= pending_change.eta,
+            errors::CustomErrors::TimelockNotMature
+        );
+        // An unused reservation would otherwise be created under the placeholder hash
+        let operation = &pending_change.operation;
+        require!(
+            matches!(operation, PendingOperation::AddReservation { .. })
+                == ctx.accounts.reservation.is_some()
+                && matches!(operation, PendingOperation::RemoveReservation { .. })
+                    == ctx.accounts.removed_reservation.is_some()
+                && matches!(operation, PendingOperation::ResumeToken { .. })
+                    == ctx.accounts.token_info.is_some(),
+            errors::CustomErrors::OperationAccountsMismatch
+        );
+
+        let platform = &mut ctx.accounts.platform;
+
+        match operation.clone() {
+            PendingOperation::UpdateConfig { params } => {
+                let old_config = platform.config();
+
+                platform.apply_config(params);
+                platform.validate()?;
+                platform.config_version += 1;
+
+                let config_change_executed_event = events::ConfigChangeExecuted {
+                    id: pending_change.id,
+                    config_version: platform.config_version,
+                    old_config,
+                    new_config: platform.config(),
+                };
+                emit!(config_change_executed_event);
+            }
+            PendingOperation::AddReservation { value, creator } => {
+                let reservation = ctx.accounts.reservation.as_mut().unwrap();
+                reservation.platform = platform.key();
+                reservation.hash = utils::get_reservation_hash(&value);
+                reservation.creator = creator;
+
+                let reservation_added_event = events::ReservationAdded {
+                    platform: reservation.platform,
+                    hash: reservation.hash,
+                    value,
+                    creator,
+                };
+                emit!(reservation_added_event);
+            }
+            PendingOperation::RemoveReservation { hash } => {
+                require!(
+                    ctx.accounts.removed_reservation.as_ref().unwrap().hash == hash,
+                    errors::CustomErrors::OperationAccountsMismatch
+                );
+
+                let reservation_removed_event = events::ReservationRemoved {
+                    platform: platform.key(),
+                    hash,
+                };
+                emit!(reservation_removed_event);
+            }
+            PendingOperation::ResumeToken { token } => {
+                let token_info = ctx.accounts.token_info.as_mut().unwrap();
+                require_keys_eq!(
+                    token_info.token,
+                    token,
+                    errors::CustomErrors::OperationAccountsMismatch
+                );
+                token_info.halted = false;
+
+                let token_resumed_event = events::TokenResumed { token };
+                emit!(token_resumed_event);
+            }
+        }
 
         Ok(())
     }
 
-    pub fn buy_tokens(ctx: Context<BuyTokens>, sol_amount: u64) -> Result<()> {
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        let config_change_cancelled_event = events::ConfigChangeCancelled {
+            id: ctx.accounts.pending_change.id,
+        };
+        emit!(config_change_cancelled_event);
+
+        Ok(())
+    }
+
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: Option<u64>) -> Result<()> {
+        let platform = &mut ctx.accounts.platform;
+        let amount = amount.unwrap_or(platform.accumulated_fees);
         require!(
-            !ctx.accounts.token_info.launched,
-            errors::CustomErrors::AlreadyLaunched
+            amount <= platform.accumulated_fees,
+            errors::CustomErrors::InsufficientFees
         );
 
-        // sol_amount = 101
-        // buy_fee = (101 * 100) / (10000 + 100) = 1
-        // sol_amount_after_fee = 100
-        let fee_amount = utils::calculate_buy_fee(
-            &(sol_amount as u128),
-            &(ctx.accounts.platform.fee_in_bps as u128),
-        );
-        let sol_amount_after_fee = sol_amount - fee_amount;
-        let token_amount = utils::get_amount_out(
-            &(sol_amount_after_fee as u128),
-            &(ctx.accounts.token_info.sol_reserve as u128),
-            &(ctx.accounts.token_info.token_reserve as u128),
+        // The platform account must stay rent exempt after the withdrawal
+        let platform_account_info = platform.to_account_info();
+        let minimum_balance = Rent::get()?.minimum_balance(platform_account_info.data_len());
+        require!(
+            platform_account_info.lamports() >= minimum_balance + amount,
+            errors::CustomErrors::RentExemptionBreached
         );
 
-        ctx.accounts.platform.accumulated_fees += fee_amount;
+        **platform_account_info.try_borrow_mut_lamports()? -= amount;
+        **ctx
+            .accounts
+            .treasury
+            .to_account_info()
+            .try_borrow_mut_lamports()? += amount;
 
-        ctx.accounts.token_info.sol_reserve += sol_amount_after_fee;
-        ctx.accounts.token_info.token_reserve -= token_amount;
+        platform.accumulated_fees -= amount;
 
-        require!(
-            ctx.accounts.token_info.sol_reserve <= ctx.accounts.token_info.target_pool_balance,
-            errors::CustomErrors::BondingCurveBreached
-        );
+        let fees_withdrawn_event = events::FeesWithdrawn {
+            treasury: ctx.accounts.treasury.key(),
+            amount,
+        };
+        emit!(fees_withdrawn_event);
 
-        // Check the current market cap and launch the token if it's been hit
-        if ctx.accounts.token_info.sol_reserve >= ctx.accounts.token_info.target_pool_balance {
-            ctx.accounts.token_info.launched = true;
-        }
+        Ok(())
+    }
 
-        // Transfer sol amount (after applying fee) from signer to token info account
-        system_program::transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: ctx.accounts.signer.to_account_info(),
-                    to: ctx.accounts.token_info.to_account_info(),
-                },
-            ),
-            sol_amount_after_fee,
-        )?;
+    pub fn withdraw_quote_fees(ctx: Context<WithdrawQuoteFees>, amount: Option<u64>) -> Result<()> {
+        let platform = &mut ctx.accounts.platform;
+        let amount = amount.unwrap_or(platform.accumulated_quote_fees);
+        require!(
+            amount <= platform.accumulated_quote_fees,
+            errors::CustomErrors::InsufficientFees
+        );
 
-        // Transfer fees (in sol) to the platform account
-        system_program::transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: ctx.accounts.signer.to_account_info(),
-                    to: ctx.accounts.platform.to_account_info(),
-                },
-            ),
-            fee_amount,
-        )?;
+        platform.accumulated_quote_fees -= amount;
 
-        let mint_token_account_key = ctx.accounts.mint.key();
+        let platform_key = platform.key();
         let seeds = &[
-            constants::seeds::TOKEN_ACCOUNT_SEED,
-            mint_token_account_key.as_ref(),
-            &[ctx.bumps.source_token_account],
+            constants::seeds::FEE_VAULT_SEED,
+            platform_key.as_ref(),
+            &[platform.fee_vault_bump],
         ];
         let signer = [&seeds[..]];
+        let quote_mint = &ctx.accounts.quote_mint;
 
-        // Transfer tokens to user
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                SplTransfer {
-                    from: ctx.accounts.source_token_account.to_account_info().clone(),
-                    to: ctx.accounts.user_token_account.to_account_info().clone(),
-                    authority: ctx.accounts.source_token_account.to_account_info().clone(),
+                TransferChecked {
+                    from: ctx.accounts.fee_vault.to_account_info(),
+                    mint: quote_mint.to_account_info(),
+                    to: ctx.accounts.treasury_quote_account.to_account_info(),
+                    authority: ctx.accounts.fee_vault.to_account_info(),
                 },
                 &signer,
             ),
-            token_amount,
+            amount,
+            quote_mint.decimals,
         )?;
 
+        let fees_withdrawn_event = events::FeesWithdrawn {
+            treasury: ctx.accounts.treasury_quote_account.key(),
+            amount,
+        };
+        emit!(fees_withdrawn_event);
+
         Ok(())
     }
 
-    pub fn sell_tokens(ctx: Context<SellTokens>, token_amount: u64, name: String) -> Result<()> {
-        require!(
-            !ctx.accounts.token_info.launched,
-            errors::CustomErrors::AlreadyLaunched
-        );
+    pub fn audit_platform(ctx: Context<AuditPlatform>) -> Result<()> {
+        let platform_account_info = ctx.accounts.platform.to_account_info();
 
-        let sol_amount = utils::get_amount_out(
-            &(token_amount as u128),
-            &(ctx.accounts.token_info.token_reserve as u128),
-            &(ctx.accounts.token_info.sol_reserve as u128),
-        );
-        let fee_amount = utils::calculate_sell_fee(
-            &(sol_amount as u128),
-            &(ctx.accounts.platform.fee_in_bps as u128),
-        );
-        let sol_amount_after_fee = sol_amount - fee_amount;
+        // The platform holds its rent exempt minimum plus the accumulated fees
+        let expected_lamports = Rent::get()?.minimum_balance(platform_account_info.data_len())
+            + ctx.accounts.platform.accumulated_fees;
+        let actual_lamports = platform_account_info.lamports();
 
-        ctx.accounts.token_info.sol_reserve -= sol_amount;
-        ctx.accounts.token_info.token_reserve += token_amount;
+        let platform_audited_event = events::PlatformAudited {
+            platform: ctx.accounts.platform.key(),
+            expected_lamports,
+            actual_lamports,
+            shortfall: expected_lamports.saturating_sub(actual_lamports),
+            surplus: actual_lamports.saturating_sub(expected_lamports),
+        };
+        emit!(platform_audited_event);
+
+        // The fee vault holds the trading fees of platforms quoted in an spl token
+        if ctx.accounts.platform.quote_mint != Pubkey::default() {
+            let Some(fee_vault) = &ctx.accounts.fee_vault else {
+                return err!(errors::CustomErrors::QuoteAccountsMissing);
+            };
+
+            let expected_amount = ctx.accounts.platform.accumulated_quote_fees;
+            let quote_vault_audited_event = events::QuoteVaultAudited {
+                vault: fee_vault.key(),
+                expected_amount,
+                actual_amount: fee_vault.amount,
+                shortfall: expected_amount.saturating_sub(fee_vault.amount),
+                surplus: fee_vault.amount.saturating_sub(expected_amount),
+            };
+            emit!(quote_vault_audited_event);
+        }
 
-        ctx.accounts.platform.accumulated_fees += fee_amount;
+        Ok(())
+    }
 
-        // Transfer tokens from user to token account
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                SplTransfer {
-                    from: ctx.accounts.user_token_account.to_account_info(),
-                    to: ctx.accounts.source_token_account.to_account_info(),
-                    authority: ctx.accounts.signer.to_account_info(),
-                },
-            ),
-            token_amount,
+    pub fn audit_token(ctx: Context<AuditToken>, halt_on_mismatch: bool) -> Result<()> {
+        let token_info = &mut ctx.accounts.token_info;
+        let token_info_account_info = token_info.to_account_info();
+
+        // The real (non virtual) reserve lives on the token info itself, or in the quote vault
+        // for tokens quoted in an spl token
+        let real_reserve = token_info
+            .sol_reserve
+            .checked_sub(token_info.virtual_sol)
+            .ok_or(errors::CustomErrors::ReserveBelowVirtualSol)?;
+        let native_reserve = if token_info.is_native_quote() {
+            real_reserve
+        } else {
+            0
+        };
+        let expected_lamports =
+            Rent::get()?.minimum_balance(token_info_account_info.data_len()) + native_reserve;
+        let actual_lamports = token_info_account_info.lamports();
+        let mut shortfall = expected_lamports.saturating_sub(actual_lamports);
+
+        if !token_info.is_native_quote() {
+            let Some(quote_vault) = &ctx.accounts.quote_vault else {
+                return err!(errors::CustomErrors::QuoteAccountsMissing);
+            };
+
+            let quote_shortfall = real_reserve.saturating_sub(quote_vault.amount);
+            shortfall += quote_shortfall;
+
+            let quote_vault_audited_event = events::QuoteVaultAudited {
+                vault: quote_vault.key(),
+                expected_amount: real_reserve,
+                actual_amount: quote_vault.amount,
+                shortfall: quote_shortfall,
+                surplus: quote_vault.amount.saturating_sub(real_reserve),
+            };
+            emit!(quote_vault_audited_event);
+        }
+
+        // Only a shortfall halts trading, anyone can top up the account with a surplus
+        if halt_on_mismatch && shortfall > 0 {
+            token_info.halted = true;
+        }
+
+        let token_audited_event = events::TokenAudited {
+            token: token_info.token,
+            expected_lamports,
+            actual_lamports,
+            shortfall,
+            surplus: actual_lamports.saturating_sub(expected_lamports),
+            halted: token_info.halted,
+        };
+        emit!(token_audited_event);
+
+        Ok(())
+    }
+
+    /// Only applies immediately without a timelock, timelocked platforms queue the resume
+    pub fn resume_token(ctx: Context<ResumeToken>) -> Result<()> {
+        ctx.accounts.token_info.halted = false;
+
+        let token_resumed_event = events::TokenResumed {
+            token: ctx.accounts.token_info.token,
+        };
+        emit!(token_resumed_event);
+
+        Ok(())
+    }
+
+    pub fn migrate_platform(ctx: Context<MigratePlatform>) -> Result<()> {
+        let platform_account_info = ctx.accounts.platform.to_account_info();
+
+        let mut platform: Platform = {
+            let data = platform_account_info.try_borrow_data()?;
+            require!(
+                data[..constants::general::DISCRIMINATOR_SIZE] == Platform::DISCRIMINATOR,
+                errors::CustomErrors::InvalidAccountLayout
+            );
+
+            match migrations::read_version(&data, migrations::PlatformV0::SIZE) {
+                0 => migrations::PlatformV0::deserialize(
+                    &mut &data[constants::general::DISCRIMINATOR_SIZE..],
+                )?
+                .into(),
+                constants::general::PLATFORM_VERSION => {
+                    return err!(errors::CustomErrors::AlreadyMigrated)
+                }
+                _ => return err!(errors::CustomErrors::InvalidAccountLayout),
+            }
+        };
+        require!(
+            platform.owner == ctx.accounts.signer.key(),
+            errors::CustomErrors::NotOwner
+        );
+        platform.bump = ctx.bumps.platform;
+
+        migrations::realloc_account(
+            &platform_account_info,
+            &ctx.accounts.signer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            constants::general::DISCRIMINATOR_SIZE + Platform::INIT_SPACE,
+            platform.accumulated_fees,
         )?;
+        platform.try_serialize(&mut &mut platform_account_info.try_borrow_mut_data()?[..])?;
+
+        let platform_migrated_event = events::PlatformMigrated {
+            platform: platform_account_info.key(),
+            version: platform.version,
+        };
+        emit!(platform_migrated_event);
+
+        Ok(())
+    }
+
+    pub fn migrate_token_info(ctx: Context<MigrateTokenInfo>, _name: String) -> Result<()> {
+        let legacy_token_info = ctx.accounts.legacy_token_info.to_account_info();
+
+        let mut token_info: TokenInfo = {
+            let data = legacy_token_info.try_borrow_data()?;
+            require!(
+                data[..constants::general::DISCRIMINATOR_SIZE] == TokenInfo::DISCRIMINATOR,
+                errors::CustomErrors::InvalidAccountLayout
+            );
+
+            match migrations::read_version(&data, migrations::TokenInfoV0::SIZE) {
+                0 => migrations::TokenInfoV0::deserialize(
+                    &mut &data[constants::general::DISCRIMINATOR_SIZE..],
+                )?
+                .into(),
+                constants::general::TOKEN_INFO_VERSION => {
+                    return err!(errors::CustomErrors::AlreadyMigrated)
+                }
+                _ => return err!(errors::CustomErrors::InvalidAccountLayout),
+            }
+        };
+        require_keys_eq!(
+            token_info.token,
+            ctx.accounts.mint.key(),
+            errors::CustomErrors::TokenMismatch
+        );
 
+        // Legacy token infos were derived from the token name, relocate them to the mint derived address
+        token_info.bump = ctx.bumps.token_info;
+        token_info.last_price_update = Clock::get()?.unix_timestamp;
+        token_info.token_account_bump = Pubkey::find_program_address(
+            &[
+                constants::seeds::TOKEN_ACCOUNT_SEED,
+                ctx.accounts.mint.key().as_ref(),
+            ],
+            &crate::ID,
+        )
+        .1;
+        ctx.accounts
+            .price_observations
+            .initialize(&token_info, ctx.bumps.price_observations);
+        ctx.accounts.token_info.set_inner(token_info);
+
+        // The legacy account holds the sol reserve on top of its rent, move the reserve over
+        // and refund the rent to the signer paying for the relocated account
+        let rent_refund = Rent::get()?.minimum_balance(legacy_token_info.data_len());
+        let legacy_lamports = legacy_token_info.lamports();
+        **legacy_token_info.try_borrow_mut_lamports()? = 0;
         **ctx
             .accounts
             .token_info
             .to_account_info()
-            .try_borrow_mut_lamports()? -= sol_amount_after_fee;
+            .try_borrow_mut_lamports()? += legacy_lamports.saturating_sub(rent_refund);
         **ctx
             .accounts
             .signer
             .to_account_info()
-            .try_borrow_mut_lamports()? += sol_amount_after_fee;
+            .try_borrow_mut_lamports()? += legacy_lamports.min(rent_refund);
 
-        **ctx
-            .accounts
-            .token_info
-            .to_account_info()
-            .try_borrow_mut_lamports()? -= fee_amount;
-        **ctx
-            .accounts
-            .platform
-            .to_account_info()
-            .try_borrow_mut_lamports()? += fee_amount;
+        legacy_token_info.assign(&system_program::ID);
+        legacy_token_info.realloc(0, false)?;
 
-        let tokens_sold_event = events::TokensSold {
-            token: ctx.accounts.mint.key(),
-            by: ctx.accounts.signer.key(),
-            amount: token_amount,
+        let token_info_migrated_event = events::TokenInfoMigrated {
+            token: ctx.accounts.token_info.token,
+            version: ctx.accounts.token_info.version,
         };
-        emit!(tokens_sold_event);
+        emit!(token_info_migrated_event);
 
         Ok(())
     }
 
-    pub fn add_liquidity(ctx: Context<AddLiquidity>, name: String) -> Result<()> {
-        require!(
-            ctx.accounts.token_info.launched,
+    pub fn create_platform_collection(
+        ctx: Context<CreatePlatformCollection>,
+        create_collection_params: CreateCollectionParams,
+    ) -> Result<()> {
+        let platform_id_seed = ctx.accounts.platform.id_seed();
+        let platform_seeds = &[
+            constants::seeds::PLATFORM_SEED,
+            &platform_id_seed,
+            &[ctx.accounts.platform.bump],
+        ];
+        let platform_signer = [&platform_seeds[..]];
+
+        // The collection is a 1/1 NFT held and controlled by the platform
+        mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    authority: ctx.accounts.platform.to_account_info(),
+                    to: ctx.accounts.collection_token_account.to_account_info(),
+                    mint: ctx.accounts.collection_mint.to_account_info(),
+                },
+                &platform_signer,
+            ),
+            1,
+        )?;
+
+        let collection_data = DataV2 {
+            name: create_collection_params.name,
+            symbol: create_collection_params.symbol,
+            uri: create_collection_params.uri,
+            seller_fee_basis_points: 0,
+            creators: Some(vec![Creator {
+                address: ctx.accounts.platform.key(),
+                verified: true,
+                share: 100,
+            }]),
+            collection: None,
+            uses: None,
+        };
+        create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                CreateMetadataAccountsV3 {
+                    payer: ctx.accounts.signer.to_account_info(),
+                    update_authority: ctx.accounts.platform.to_account_info(),
+                    mint: ctx.accounts.collection_mint.to_account_info(),
+                    metadata: ctx.accounts.collection_metadata.to_account_info(),
+                    mint_authority: ctx.accounts.platform.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+                &platform_signer,
+            ),
+            collection_data,
+            true,
+            true,
+            Some(CollectionDetails::V1 { size: 0 }),
+        )?;
+
+        // The master edition takes over the mint and freeze authorities, capping the supply at one
+        create_master_edition_v3(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                CreateMasterEditionV3 {
+                    edition: ctx.accounts.collection_master_edition.to_account_info(),
+                    mint: ctx.accounts.collection_mint.to_account_info(),
+                    update_authority: ctx.accounts.platform.to_account_info(),
+                    mint_authority: ctx.accounts.platform.to_account_info(),
+                    payer: ctx.accounts.signer.to_account_info(),
+                    metadata: ctx.accounts.collection_metadata.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+                &platform_signer,
+            ),
+            Some(0),
+        )?;
+
+        ctx.accounts.platform.collection_mint = ctx.accounts.collection_mint.key();
+
+        let platform_collection_created_event = events::PlatformCollectionCreated {
+            platform: ctx.accounts.platform.key(),
+            collection_mint: ctx.accounts.collection_mint.key(),
+        };
+        emit!(platform_collection_created_event);
+
+        Ok(())
+    }
+
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        ctx.accounts.referral.referrer = ctx.accounts.signer.key();
+        ctx.accounts.referral.bump = ctx.bumps.referral;
+
+        let referrer_registered_event = events::ReferrerRegistered {
+            referrer: ctx.accounts.signer.key(),
+        };
+        emit!(referrer_registered_event);
+
+        Ok(())
+    }
+
+    pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>) -> Result<()> {
+        let referral = &mut ctx.accounts.referral;
+        let unclaimed_fees = referral.unclaimed_fees;
+        require!(unclaimed_fees > 0, errors::CustomErrors::NothingToClaim);
+
+        **referral.to_account_info().try_borrow_mut_lamports()? -= unclaimed_fees;
+        **ctx
+            .accounts
+            .signer
+            .to_account_info()
+            .try_borrow_mut_lamports()? += unclaimed_fees;
+
+        referral.unclaimed_fees = 0;
+
+        let referral_fees_claimed_event = events::ReferralFeesClaimed {
+            referrer: referral.referrer,
+            amount: unclaimed_fees,
+        };
+        emit!(referral_fees_claimed_event);
+
+        Ok(())
+    }
+
+    pub fn create_token(
+        ctx: Context<CreateToken>,
+        create_token_params: CreateTokenParams,
+    ) -> Result<()> {
+        let allowlisted = ctx.accounts.allowed_creator.is_some();
+        launch::check_launch(
+            &ctx.accounts.platform,
+            &ctx.accounts.signer.key(),
+            allowlisted,
+            &ctx.accounts.name_reservation,
+            &ctx.accounts.symbol_reservation,
+            &create_token_params,
+        )?;
+        let creation_fee = launch::collect_creation_fee(
+            &mut ctx.accounts.platform,
+            &ctx.accounts.signer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            allowlisted,
+        )?;
+
+        let platform_key = ctx.accounts.platform.key();
+        let seeds = &[
+            constants::seeds::MINT_SEED,
+            platform_key.as_ref(),
+            create_token_params.name.as_bytes(),
+            &[ctx.bumps.mint],
+        ];
+        let signer = [&seeds[..]];
+
+        // First, create the token metadata
+        // The token was already created by Anchor in the background
+        // It starts out mutable under the mint so the creator and collection can be verified first
+        let collection_mint = ctx.accounts.platform.collection_mint;
+        let has_collection = collection_mint != Pubkey::default();
+        let token_data: DataV2 = DataV2 {
+            name: create_token_params.name.clone(),
+            symbol: create_token_params.symbol.clone(),
+            uri: create_token_params.uri.clone(),
+            seller_fee_basis_points: 0,
+            creators: Some(vec![Creator {
+                address: ctx.accounts.signer.key(),
+                verified: false,
+                share: 100,
+            }]),
+            collection: has_collection.then_some(Collection {
+                verified: false,
+                key: collection_mint,
+            }),
+            uses: None,
+        };
+        let metadata_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                payer: ctx.accounts.signer.to_account_info(),
+                update_authority: ctx.accounts.mint.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                metadata: ctx.accounts.metadata.to_account_info(),
+                mint_authority: ctx.accounts.mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            &signer,
+        );
+        create_metadata_accounts_v3(metadata_ctx, token_data, true, true, None)?;
+
+        // The creator signs the transaction, so they can verify themselves as the token creator
+        sign_metadata(CpiContext::new(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            SignMetadata {
+                creator: ctx.accounts.signer.to_account_info(),
+                metadata: ctx.accounts.metadata.to_account_info(),
+            },
+        ))?;
+
+        // The platform, as the collection authority, vouches for the token being launched here
+        if has_collection {
+            let (Some(collection_mint), Some(collection_metadata), Some(collection_master_edition)) = (
+                &ctx.accounts.collection_mint,
+                &ctx.accounts.collection_metadata,
+                &ctx.accounts.collection_master_edition,
+            ) else {
+                return err!(errors::CustomErrors::CollectionAccountsMissing);
+            };
+
+            let platform_id_seed = ctx.accounts.platform.id_seed();
+            let platform_seeds = &[
+                constants::seeds::PLATFORM_SEED,
+                &platform_id_seed,
+                &[ctx.accounts.platform.bump],
+            ];
+            let platform_signer = [&platform_seeds[..]];
+
+            verify_sized_collection_item(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_metadata_program.to_account_info(),
+                    VerifySizedCollectionItem {
+                        payer: ctx.accounts.signer.to_account_info(),
+                        metadata: ctx.accounts.metadata.to_account_info(),
+                        collection_authority: ctx.accounts.platform.to_account_info(),
+                        collection_mint: collection_mint.to_account_info(),
+                        collection_metadata: collection_metadata.to_account_info(),
+                        collection_master_edition: collection_master_edition.to_account_info(),
+                    },
+                    &platform_signer,
+                ),
+                None,
+            )?;
+        }
+
+        // Then, settle the metadata authority the creator asked for
+        let metadata_update_authority = match create_token_params.metadata_authority {
+            MetadataAuthority::Immutable => None,
+            MetadataAuthority::Creator => Some(ctx.accounts.signer.key()),
+            MetadataAuthority::Program => Some(ctx.accounts.mint.key()),
+        };
+        if create_token_params.metadata_authority != MetadataAuthority::Program {
+            update_metadata_accounts_v2(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_metadata_program.to_account_info(),
+                    UpdateMetadataAccountsV2 {
+                        metadata: ctx.accounts.metadata.to_account_info(),
+                        update_authority: ctx.accounts.mint.to_account_info(),
+                    },
+                    &signer,
+                ),
+                metadata_update_authority,
+                None,
+                None,
+                Some(metadata_update_authority.is_some()),
+            )?;
+        }
+
+        // Second, initialize the token campaign params
+        let creator_allocation = launch::initialize_campaign(
+            &ctx.accounts.platform,
+            &mut ctx.accounts.token_info,
+            &mut ctx.accounts.price_observations,
+            ctx.accounts.token_profile.as_deref_mut().map(|p| &mut **p),
+            ctx.accounts.mint.key(),
+            ctx.accounts.signer.key(),
+            &create_token_params,
+            TokenInfoBumps {
+                token_info: ctx.bumps.token_info,
+                token_account: ctx.bumps.token_account,
+                quote_vault: ctx.bumps.quote_vault.unwrap_or_default(),
+            },
+            ctx.bumps.price_observations,
+            ctx.accounts.quote_vault.is_some(),
+        )?;
+
+        // Then, lock the creator allocation in its vesting escrow
+        if creator_allocation > 0 {
+            let (Some(vesting), Some(vesting_token_account)) = (
+                ctx.accounts.vesting.as_mut(),
+                ctx.accounts.vesting_token_account.as_ref(),
+            ) else {
+                return err!(errors::CustomErrors::VestingAccountsMissing);
+            };
+
+            launch::lock_creator_allocation(
+                vesting,
+                &vesting_token_account.to_account_info(),
+                &ctx.accounts.mint.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                &signer,
+                ctx.accounts.signer.key(),
+                creator_allocation,
+                &create_token_params,
+            )?;
+        } else {
+            // Nothing to vest, don't charge the creator rent for an empty escrow
+            require!(
+                ctx.accounts.vesting.is_none() && ctx.accounts.vesting_token_account.is_none(),
+                errors::CustomErrors::UnexpectedVestingAccounts
+            );
+        }
+
+        // Lastly, mint the curve supply and revoke the mint authority
+        let mint = launch::mint_curve_supply(
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.token_account.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &signer,
+            ctx.accounts.token_info.token_reserve,
+        )?;
+
+        let token_created_event = events::TokenCreated {
+            platform: ctx.accounts.platform.key(),
+            token: ctx.accounts.mint.key(),
+            quote_mint: ctx.accounts.token_info.quote_mint,
+            creation_fee,
+            mint_authority: mint.mint_authority.into(),
+            freeze_authority: mint.freeze_authority.into(),
+            metadata_update_authority,
+        };
+        emit!(token_created_event);
+
+        Ok(())
+    }
+
+    pub fn create_token_2022(
+        ctx: Context<CreateToken2022>,
+        create_token_params: CreateTokenParams,
+        transfer_fee_params: Option<TransferFeeParams>,
+    ) -> Result<()> {
+        if let Some(transfer_fee_params) = &transfer_fee_params {
+            transfer_fee_params.validate()?;
+        }
+
+        let allowlisted = ctx.accounts.allowed_creator.is_some();
+        launch::check_launch(
+            &ctx.accounts.platform,
+            &ctx.accounts.signer.key(),
+            allowlisted,
+            &ctx.accounts.name_reservation,
+            &ctx.accounts.symbol_reservation,
+            &create_token_params,
+        )?;
+        let creation_fee = launch::collect_creation_fee(
+            &mut ctx.accounts.platform,
+            &ctx.accounts.signer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            allowlisted,
+        )?;
+
+        let platform_key = ctx.accounts.platform.key();
+        let seeds = &[
+            constants::seeds::MINT_SEED,
+            platform_key.as_ref(),
+            create_token_params.name.as_bytes(),
+            &[ctx.bumps.mint],
+        ];
+        let signer = [&seeds[..]];
+
+        // First, create the mint with its metadata stored on the mint itself
+        // Program managed metadata updates go through Metaplex, which Token-2022 mints don't use
+        let metadata_update_authority = match create_token_params.metadata_authority {
+            MetadataAuthority::Immutable => None,
+            MetadataAuthority::Creator => Some(ctx.accounts.signer.key()),
+            MetadataAuthority::Program => {
+                return err!(errors::CustomErrors::UnsupportedMetadataAuthority)
+            }
+        };
+        token_extensions::create_mint(
+            &ctx.accounts.signer.to_account_info(),
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &signer,
+            &create_token_params,
+            transfer_fee_params.as_ref(),
+            &ctx.accounts.signer.key(),
+            metadata_update_authority,
+        )?;
+
+        let mint_key = ctx.accounts.mint.key();
+        let token_account_seeds = &[
+            constants::seeds::TOKEN_ACCOUNT_SEED,
+            mint_key.as_ref(),
+            &[ctx.bumps.token_account],
+        ];
+        token_extensions::create_token_account(
+            &ctx.accounts.signer.to_account_info(),
+            &ctx.accounts.token_account.to_account_info(),
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &[&token_account_seeds[..]],
+        )?;
+
+        // Second, initialize the token campaign params
+        let creator_allocation = launch::initialize_campaign(
+            &ctx.accounts.platform,
+            &mut ctx.accounts.token_info,
+            &mut ctx.accounts.price_observations,
+            ctx.accounts.token_profile.as_deref_mut().map(|p| &mut **p),
+            mint_key,
+            ctx.accounts.signer.key(),
+            &create_token_params,
+            TokenInfoBumps {
+                token_info: ctx.bumps.token_info,
+                token_account: ctx.bumps.token_account,
+                quote_vault: ctx.bumps.quote_vault.unwrap_or_default(),
+            },
+            ctx.bumps.price_observations,
+            ctx.accounts.quote_vault.is_some(),
+        )?;
+
+        // Then, lock the creator allocation in its vesting escrow
+        if creator_allocation > 0 {
+            let (Some(vesting), Some(vesting_token_account), Some(vesting_token_account_bump)) = (
+                ctx.accounts.vesting.as_mut(),
+                ctx.accounts.vesting_token_account.as_ref(),
+                ctx.bumps.vesting_token_account,
+            ) else {
+                return err!(errors::CustomErrors::VestingAccountsMissing);
+            };
+
+            let vesting_token_account_seeds = &[
+                constants::seeds::VESTING_ACCOUNT_SEED,
+                mint_key.as_ref(),
+                &[vesting_token_account_bump],
+            ];
+            token_extensions::create_token_account(
+                &ctx.accounts.signer.to_account_info(),
+                &vesting_token_account.to_account_info(),
+                &ctx.accounts.mint.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                &[&vesting_token_account_seeds[..]],
+            )?;
+
+            launch::lock_creator_allocation(
+                vesting,
+                &vesting_token_account.to_account_info(),
+                &ctx.accounts.mint.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                &signer,
+                ctx.accounts.signer.key(),
+                creator_allocation,
+                &create_token_params,
+            )?;
+        } else {
+            // Nothing to vest, don't charge the creator rent for an empty escrow
+            require!(
+                ctx.accounts.vesting.is_none() && ctx.accounts.vesting_token_account.is_none(),
+                errors::CustomErrors::UnexpectedVestingAccounts
+            );
+        }
+
+        // Lastly, mint the curve supply and revoke the mint authority
+        let mint = launch::mint_curve_supply(
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.token_account.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &signer,
+            ctx.accounts.token_info.token_reserve,
+        )?;
+
+        let token_created_event = events::TokenCreated {
+            platform: ctx.accounts.platform.key(),
+            token: mint_key,
+            quote_mint: ctx.accounts.token_info.quote_mint,
+            creation_fee,
+            mint_authority: mint.mint_authority.into(),
+            freeze_authority: mint.freeze_authority.into(),
+            metadata_update_authority,
+        };
+        emit!(token_created_event);
+
+        Ok(())
+    }
+
+    pub fn update_token_metadata(
+        ctx: Context<TokenMetadataOperation>,
+        symbol: Option<String>,
+        uri: Option<String>,
+    ) -> Result<()> {
+        let metadata = &ctx.accounts.metadata;
+        require!(metadata.is_mutable, errors::CustomErrors::MetadataLocked);
+
+        // The mint signs as update authority, it was derived from the name kept in its
+        // metadata, which Metaplex pads with null bytes
+        let name = metadata.name.trim_end_matches('\0');
+        let (platform_seed, mint_bump) = utils::find_mint_seeds(
+            &ctx.accounts.mint.key(),
+            &ctx.accounts.token_info.platform,
+            name,
+        )
+        .ok_or(errors::CustomErrors::MetadataNotProgramManaged)?;
+        let seeds = &[
+            constants::seeds::MINT_SEED,
+            &platform_seed,
+            name.as_bytes(),
+            &[mint_bump],
+        ];
+        let signer = [&seeds[..]];
+
+        // Metaplex replaces the whole data, so carry over the fields that aren't updated
+        let token_data = DataV2 {
+            name: metadata.name.clone(),
+            symbol: symbol.unwrap_or(metadata.symbol.clone()),
+            uri: uri.unwrap_or(metadata.uri.clone()),
+            seller_fee_basis_points: metadata.seller_fee_basis_points,
+            creators: metadata.creators.clone(),
+            collection: metadata.collection.clone(),
+            uses: metadata.uses.clone(),
+        };
+        let token_metadata_updated_event = events::TokenMetadataUpdated {
+            token: ctx.accounts.mint.key(),
+            symbol: token_data.symbol.clone(),
+            uri: token_data.uri.clone(),
+        };
+
+        update_metadata_accounts_v2(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                UpdateMetadataAccountsV2 {
+                    metadata: ctx.accounts.metadata.to_account_info(),
+                    update_authority: ctx.accounts.mint.to_account_info(),
+                },
+                &signer,
+            ),
+            None,
+            Some(token_data),
+            None,
+            None,
+        )?;
+
+        emit!(token_metadata_updated_event);
+
+        Ok(())
+    }
+
+    pub fn lock_metadata(ctx: Context<TokenMetadataOperation>) -> Result<()> {
+        let metadata = &ctx.accounts.metadata;
+        require!(metadata.is_mutable, errors::CustomErrors::MetadataLocked);
+
+        // The mint signs as update authority, it was derived from the name kept in its
+        // metadata, which Metaplex pads with null bytes
+        let name = metadata.name.trim_end_matches('\0');
+        let (platform_seed, mint_bump) = utils::find_mint_seeds(
+            &ctx.accounts.mint.key(),
+            &ctx.accounts.token_info.platform,
+            name,
+        )
+        .ok_or(errors::CustomErrors::MetadataNotProgramManaged)?;
+        let seeds = &[
+            constants::seeds::MINT_SEED,
+            &platform_seed,
+            name.as_bytes(),
+            &[mint_bump],
+        ];
+        let signer = [&seeds[..]];
+
+        // Metaplex rejects every later update once the metadata is immutable
+        update_metadata_accounts_v2(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                UpdateMetadataAccountsV2 {
+                    metadata: ctx.accounts.metadata.to_account_info(),
+                    update_authority: ctx.accounts.mint.to_account_info(),
+                },
+                &signer,
+            ),
+            None,
+            None,
+            None,
+            Some(false),
+        )?;
+
+        let metadata_locked_event = events::MetadataLocked {
+            token: ctx.accounts.mint.key(),
+        };
+        emit!(metadata_locked_event);
+
+        Ok(())
+    }
+
+    pub fn update_token_profile(
+        ctx: Context<UpdateTokenProfile>,
+        token_profile_params: TokenProfileParams,
+    ) -> Result<()> {
+        let token_profile = &mut ctx.accounts.token_profile;
+        token_profile.apply(&token_profile_params)?;
+
+        let token_profile_updated_event = events::TokenProfileUpdated {
+            token: token_profile.token,
+            content_hash: token_profile.content_hash,
+        };
+        emit!(token_profile_updated_event);
+
+        Ok(())
+    }
+
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        // The allocation was never bought on the curve, selling it into the curve would pay out
+        // the reserve of other buyers, so it stays locked until the token graduates
+        require!(
+            ctx.accounts.token_info.launched,
+            errors::CustomErrors::NotLaunched
+        );
+
+        let vesting = &mut ctx.accounts.vesting;
+
+        let vested_amount = utils::calculate_vested_amount(
+            vesting.total_amount,
+            vesting.start_time,
+            vesting.cliff_time,
+            vesting.end_time,
+            Clock::get()?.unix_timestamp,
+        );
+        let claimable_amount = vested_amount - vesting.claimed_amount;
+        require!(claimable_amount > 0, errors::CustomErrors::NothingToClaim);
+
+        vesting.claimed_amount += claimable_amount;
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            constants::seeds::VESTING_ACCOUNT_SEED,
+            mint_key.as_ref(),
+            &[ctx.bumps.vesting_token_account],
+        ];
+        let signer = [&seeds[..]];
+
+        // Release the unlocked tokens to the beneficiary
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vesting_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.vesting_token_account.to_account_info(),
+                },
+                &signer,
+            ),
+            claimable_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        let vested_tokens_claimed_event = events::VestedTokensClaimed {
+            token: mint_key,
+            beneficiary: ctx.accounts.signer.key(),
+            amount: claimable_amount,
+        };
+        emit!(vested_tokens_claimed_event);
+
+        Ok(())
+    }
+
+    /// Opens the signer's associated token account for `mint`, a no-op if it already exists
+    pub fn init_token_account_for_token(_: Context<InitAccountsForToken>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn buy_tokens(ctx: Context<BuyTokens>, sol_amount: u64) -> Result<()> {
+        ctx.accounts.token_info.check_tradable()?;
+
+        let now = Clock::get()?.unix_timestamp;
+
+        // sol_amount = 101
+        // buy_fee = (101 * 100) / (10000 + 100) = 1
+        // sol_amount_after_fee = 100
+        let fee_in_bps = ctx
+            .accounts
+            .platform
+            .current_fee_in_bps(&ctx.accounts.token_info, now);
+        let fee_amount = utils::calculate_buy_fee(&(sol_amount as u128), &(fee_in_bps as u128));
+        let sol_amount_after_fee = sol_amount - fee_amount;
+
+        // The referrer's share is carved out of the protocol fee
+        let referral_fee_amount = match ctx.accounts.referral {
+            Some(_) => utils::calculate_referral_fee(
+                &(fee_amount as u128),
+                &(ctx.accounts.platform.referral_fee_in_bps as u128),
+            ),
+            None => 0,
+        };
+        let platform_fee_amount = fee_amount - referral_fee_amount;
+
+        // Referral balances are held in sol, so referrals only apply to sol quoted tokens
+        require!(
+            ctx.accounts.token_info.is_native_quote() || ctx.accounts.referral.is_none(),
+            errors::CustomErrors::ReferralRequiresNativeQuote
+        );
+
+        if ctx.accounts.token_info.is_native_quote() {
+            ctx.accounts.platform.accumulated_fees += platform_fee_amount;
+        } else {
+            ctx.accounts.platform.accumulated_quote_fees += platform_fee_amount;
+        }
+
+        let token_amount = ctx.accounts.token_info.buy(sol_amount_after_fee, now)?;
+        ctx.accounts
+            .price_observations
+            .record(&ctx.accounts.token_info);
+
+        if ctx.accounts.token_info.is_native_quote() {
+            // Transfer sol amount (after applying fee) from signer to token info account
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.signer.to_account_info(),
+                        to: ctx.accounts.token_info.to_account_info(),
+                    },
+                ),
+                sol_amount_after_fee,
+            )?;
+
+            // Transfer fees (in sol) to the platform account
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.signer.to_account_info(),
+                        to: ctx.accounts.platform.to_account_info(),
+                    },
+                ),
+                platform_fee_amount,
+            )?;
+        } else {
+            let (
+                Some(quote_mint),
+                Some(user_quote_account),
+                Some(quote_vault),
+                Some(fee_vault),
+                Some(quote_token_program),
+            ) = (
+                &ctx.accounts.quote_mint,
+                &ctx.accounts.user_quote_account,
+                &ctx.accounts.quote_vault,
+                &ctx.accounts.fee_vault,
+                &ctx.accounts.quote_token_program,
+            )
+            else {
+                return err!(errors::CustomErrors::QuoteAccountsMissing);
+            };
+
+            // Transfer quote amount (after applying fee) from signer to the quote vault
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    quote_token_program.to_account_info(),
+                    TransferChecked {
+                        from: user_quote_account.to_account_info(),
+                        mint: quote_mint.to_account_info(),
+                        to: quote_vault.to_account_info(),
+                        authority: ctx.accounts.signer.to_account_info(),
+                    },
+                ),
+                sol_amount_after_fee,
+                quote_mint.decimals,
+            )?;
+
+            // Transfer fees (in the quote token) to the platform fee vault
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    quote_token_program.to_account_info(),
+                    TransferChecked {
+                        from: user_quote_account.to_account_info(),
+                        mint: quote_mint.to_account_info(),
+                        to: fee_vault.to_account_info(),
+                        authority: ctx.accounts.signer.to_account_info(),
+                    },
+                ),
+                platform_fee_amount,
+                quote_mint.decimals,
+            )?;
+        }
+
+        if let Some(referral) = ctx.accounts.referral.as_mut() {
+            // Transfer the referral fee (in sol) to the referral account
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.signer.to_account_info(),
+                        to: referral.to_account_info(),
+                    },
+                ),
+                referral_fee_amount,
+            )?;
+
+            referral.unclaimed_fees += referral_fee_amount;
+            referral.total_fees_earned += referral_fee_amount;
+            referral.referral_volume += sol_amount;
+            referral.trade_count += 1;
+
+            let referral_fee_paid_event = events::ReferralFeePaid {
+                referrer: referral.referrer,
+                token: ctx.accounts.mint.key(),
+                trader: ctx.accounts.signer.key(),
+                volume: sol_amount,
+                amount: referral_fee_amount,
+            };
+            emit!(referral_fee_paid_event);
+        }
+
+        let mint_token_account_key = ctx.accounts.mint.key();
+        let seeds = &[
+            constants::seeds::TOKEN_ACCOUNT_SEED,
+            mint_token_account_key.as_ref(),
+            &[ctx.accounts.token_info.token_account_bump],
+        ];
+        let signer = [&seeds[..]];
+
+        // Transfer tokens to user
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.source_token_account.to_account_info().clone(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info().clone(),
+                    authority: ctx.accounts.source_token_account.to_account_info().clone(),
+                },
+                &signer,
+            ),
+            token_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        ctx.accounts.user_token_account.reload()?;
+        ctx.accounts
+            .token_info
+            .check_holding(ctx.accounts.user_token_account.amount)?;
+
+        let tokens_bought_event = events::TokensBought {
+            token: ctx.accounts.mint.key(),
+            by: ctx.accounts.signer.key(),
+            amount: token_amount,
+        };
+        emit!(tokens_bought_event);
+
+        Ok(())
+    }
+
+    pub fn sell_tokens(ctx: Context<SellTokens>, token_amount: u64) -> Result<()> {
+        ctx.accounts.token_info.check_tradable()?;
+
+        let now = Clock::get()?.unix_timestamp;
+
+        // Only the amount left after the token's transfer fee reaches the curve
+        let received_token_amount = token_amount
+            - token_extensions::get_transfer_fee(
+                &ctx.accounts.mint.to_account_info(),
+                token_amount,
+            )?;
+        let fee_in_bps = ctx
+            .accounts
+            .platform
+            .current_fee_in_bps(&ctx.accounts.token_info, now);
+        let sol_amount = ctx.accounts.token_info.sell(received_token_amount, now);
+        ctx.accounts
+            .price_observations
+            .record(&ctx.accounts.token_info);
+        let fee_amount = utils::calculate_sell_fee(&(sol_amount as u128), &(fee_in_bps as u128));
+        let sol_amount_after_fee = sol_amount - fee_amount;
+
+        // The referrer's share is carved out of the protocol fee
+        let referral_fee_amount = match ctx.accounts.referral {
+            Some(_) => utils::calculate_referral_fee(
+                &(fee_amount as u128),
+                &(ctx.accounts.platform.referral_fee_in_bps as u128),
+            ),
+            None => 0,
+        };
+        let platform_fee_amount = fee_amount - referral_fee_amount;
+
+        // Referral balances are held in sol, so referrals only apply to sol quoted tokens
+        require!(
+            ctx.accounts.token_info.is_native_quote() || ctx.accounts.referral.is_none(),
+            errors::CustomErrors::ReferralRequiresNativeQuote
+        );
+
+        if ctx.accounts.token_info.is_native_quote() {
+            ctx.accounts.platform.accumulated_fees += platform_fee_amount;
+        } else {
+            ctx.accounts.platform.accumulated_quote_fees += platform_fee_amount;
+        }
+
+        // Transfer tokens from user to token account
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.source_token_account.to_account_info(),
+                    authority: ctx.accounts.signer.to_account_info(),
+                },
+            ),
+            token_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        if ctx.accounts.token_info.is_native_quote() {
+            **ctx
+                .accounts
+                .token_info
+                .to_account_info()
+                .try_borrow_mut_lamports()? -= sol_amount_after_fee;
+            **ctx
+                .accounts
+                .signer
+                .to_account_info()
+                .try_borrow_mut_lamports()? += sol_amount_after_fee;
+
+            **ctx
+                .accounts
+                .token_info
+                .to_account_info()
+                .try_borrow_mut_lamports()? -= fee_amount;
+            **ctx
+                .accounts
+                .platform
+                .to_account_info()
+                .try_borrow_mut_lamports()? += platform_fee_amount;
+        } else {
+            let (
+                Some(quote_mint),
+                Some(user_quote_account),
+                Some(quote_vault),
+                Some(fee_vault),
+                Some(quote_token_program),
+            ) = (
+                &ctx.accounts.quote_mint,
+                &ctx.accounts.user_quote_account,
+                &ctx.accounts.quote_vault,
+                &ctx.accounts.fee_vault,
+                &ctx.accounts.quote_token_program,
+            )
+            else {
+                return err!(errors::CustomErrors::QuoteAccountsMissing);
+            };
+
+            let mint_key = ctx.accounts.mint.key();
+            let quote_vault_seeds = &[
+                constants::seeds::QUOTE_VAULT_SEED,
+                mint_key.as_ref(),
+                &[ctx.accounts.token_info.quote_vault_bump],
+            ];
+            let quote_vault_signer = [&quote_vault_seeds[..]];
+
+            // Transfer quote amount (after applying fee) from the quote vault to signer
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    quote_token_program.to_account_info(),
+                    TransferChecked {
+                        from: quote_vault.to_account_info(),
+                        mint: quote_mint.to_account_info(),
+                        to: user_quote_account.to_account_info(),
+                        authority: quote_vault.to_account_info(),
+                    },
+                    &quote_vault_signer,
+                ),
+                sol_amount_after_fee,
+                quote_mint.decimals,
+            )?;
+
+            // Transfer fees (in the quote token) from the quote vault to the platform fee vault
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    quote_token_program.to_account_info(),
+                    TransferChecked {
+                        from: quote_vault.to_account_info(),
+                        mint: quote_mint.to_account_info(),
+                        to: fee_vault.to_account_info(),
+                        authority: quote_vault.to_account_info(),
+                    },
+                    &quote_vault_signer,
+                ),
+                platform_fee_amount,
+                quote_mint.decimals,
+            )?;
+        }
+
+        if let Some(referral) = ctx.accounts.referral.as_mut() {
+            **referral.to_account_info().try_borrow_mut_lamports()? += referral_fee_amount;
+
+            referral.unclaimed_fees += referral_fee_amount;
+            referral.total_fees_earned += referral_fee_amount;
+            referral.referral_volume += sol_amount;
+            referral.trade_count += 1;
+
+            let referral_fee_paid_event = events::ReferralFeePaid {
+                referrer: referral.referrer,
+                token: ctx.accounts.mint.key(),
+                trader: ctx.accounts.signer.key(),
+                volume: sol_amount,
+                amount: referral_fee_amount,
+            };
+            emit!(referral_fee_paid_event);
+        }
+
+        let tokens_sold_event = events::TokensSold {
+            token: ctx.accounts.mint.key(),
+            by: ctx.accounts.signer.key(),
+            amount: token_amount,
+        };
+        emit!(tokens_sold_event);
+
+        Ok(())
+    }
+
+    /// Quotes a buy of `sol_amount` at the current curve state and fee schedule without trading,
+    /// clients read the quote from the return data of a simulation
+    pub fn quote_buy(ctx: Context<QuoteTrade>, sol_amount: u64) -> Result<TradeQuote> {
+        ctx.accounts.token_info.check_tradable()?;
+
+        let now = Clock::get()?.unix_timestamp;
+
+        let fee_in_bps = ctx
+            .accounts
+            .platform
+            .current_fee_in_bps(&ctx.accounts.token_info, now);
+        let fee_amount = utils::calculate_buy_fee(&(sol_amount as u128), &(fee_in_bps as u128));
+        // Trade against a copy of the curve, quoting must not move it
+        let token_amount = ctx
+            .accounts
+            .token_info
+            .clone()
+            .buy(sol_amount - fee_amount, now)?;
+
+        // The buyer receives the tokens net of the token's transfer fee
+        let received_token_amount = token_amount
+            - token_extensions::get_transfer_fee(
+                &ctx.accounts.mint.to_account_info(),
+                token_amount,
+            )?;
+
+        Ok(TradeQuote {
+            fee_in_bps,
+            fee_amount,
+            amount_in: sol_amount,
+            amount_out: received_token_amount,
+        })
+    }
+
+    /// Quotes a sell of `token_amount` at the current curve state and fee schedule without trading,
+    /// clients read the quote from the return data of a simulation
+    pub fn quote_sell(ctx: Context<QuoteTrade>, token_amount: u64) -> Result<TradeQuote> {
+        ctx.accounts.token_info.check_tradable()?;
+
+        let now = Clock::get()?.unix_timestamp;
+
+        // Only the amount left after the token's transfer fee reaches the curve
+        let received_token_amount = token_amount
+            - token_extensions::get_transfer_fee(
+                &ctx.accounts.mint.to_account_info(),
+                token_amount,
+            )?;
+        let fee_in_bps = ctx
+            .accounts
+            .platform
+            .current_fee_in_bps(&ctx.accounts.token_info, now);
+        // Trade against a copy of the curve, quoting must not move it
+        let sol_amount = ctx
+            .accounts
+            .token_info
+            .clone()
+            .sell(received_token_amount, now);
+        let fee_amount = utils::calculate_sell_fee(&(sol_amount as u128), &(fee_in_bps as u128));
+
+        Ok(TradeQuote {
+            fee_in_bps,
+            fee_amount,
+            amount_in: token_amount,
+            amount_out: sol_amount - fee_amount,
+        })
+    }
+
+    /// Accrues the price held since the last trade and records it, so consumers can take a fresh
+    /// observation between trades. Permissionless, it leaves the curve untouched
+    pub fn observe_price(ctx: Context<ObservePrice>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        ctx.accounts.token_info.accumulate_price(now);
+        ctx.accounts
+            .price_observations
+            .record(&ctx.accounts.token_info);
+
+        let price_observed_event = events::PriceObserved {
+            token: ctx.accounts.token_info.token,
+            timestamp: now,
+            price_cumulative: ctx.accounts.token_info.price_cumulative,
+        };
+        emit!(price_observed_event);
+
+        Ok(())
+    }
+
+    /// Sells one token and buys another with the proceeds. Both tokens must trade on `platform`
+    /// and be quoted in native sol, anything else is rejected rather than routed
+    pub fn swap_tokens(
+        ctx: Context<SwapTokens>,
+        token_amount: u64,
+        min_token_amount_out: u64,
+    ) -> Result<()> {
+        ctx.accounts.sell_token_info.check_tradable()?;
+        ctx.accounts.buy_token_info.check_tradable()?;
+
+        // The proceeds move between the curves in sol, so both tokens must be sol quoted
+        require!(
+            ctx.accounts.sell_token_info.is_native_quote()
+                && ctx.accounts.buy_token_info.is_native_quote(),
+            errors::CustomErrors::NativeQuoteRequired
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+
+        // Sell the tokens on the first curve, only the amount left after its transfer fee reaches the curve
+        let received_token_amount = token_amount
+            - token_extensions::get_transfer_fee(
+                &ctx.accounts.sell_mint.to_account_info(),
+                token_amount,
+            )?;
+        let sell_fee_in_bps = ctx
+            .accounts
+            .platform
+            .current_fee_in_bps(&ctx.accounts.sell_token_info, now);
+        let sol_amount = ctx
+            .accounts
+            .sell_token_info
+            .sell(received_token_amount, now);
+        ctx.accounts
+            .sell_price_observations
+            .record(&ctx.accounts.sell_token_info);
+        let sell_fee_amount =
+            utils::calculate_sell_fee(&(sol_amount as u128), &(sell_fee_in_bps as u128));
+        let proceeds = sol_amount - sell_fee_amount;
+
+        // Buy on the second curve with the proceeds
+        let buy_fee_in_bps = ctx
+            .accounts
+            .platform
+            .current_fee_in_bps(&ctx.accounts.buy_token_info, now);
+        let buy_fee_amount =
+            utils::calculate_buy_fee(&(proceeds as u128), &(buy_fee_in_bps as u128));
+        let sol_amount_after_fee = proceeds - buy_fee_amount;
+        let bought_token_amount = ctx.accounts.buy_token_info.buy(sol_amount_after_fee, now)?;
+        ctx.accounts
+            .buy_price_observations
+            .record(&ctx.accounts.buy_token_info);
+        // The user receives the bought tokens net of their transfer fee
+        let received_bought_token_amount = bought_token_amount
+            - token_extensions::get_transfer_fee(
+                &ctx.accounts.buy_mint.to_account_info(),
+                bought_token_amount,
+            )?;
+        require!(
+            received_bought_token_amount >= min_token_amount_out,
+            errors::CustomErrors::SlippageExceeded
+        );
+
+        ctx.accounts.platform.accumulated_fees += sell_fee_amount + buy_fee_amount;
+
+        // Transfer the sold tokens from user to the first token account
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.sell_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.sell_user_token_account.to_account_info(),
+                    mint: ctx.accounts.sell_mint.to_account_info(),
+                    to: ctx.accounts.sell_source_token_account.to_account_info(),
+                    authority: ctx.accounts.signer.to_account_info(),
+                },
+            ),
+            token_amount,
+            ctx.accounts.sell_mint.decimals,
+        )?;
+
+        // Move the sol between the curves, and both fees to the platform account
+        **ctx
+            .accounts
+            .sell_token_info
+            .to_account_info()
+            .try_borrow_mut_lamports()? -= sol_amount;
+        **ctx
+            .accounts
+            .buy_token_info
+            .to_account_info()
+            .try_borrow_mut_lamports()? += sol_amount_after_fee;
+        **ctx
+            .accounts
+            .platform
+            .to_account_info()
+            .try_borrow_mut_lamports()? += sell_fee_amount + buy_fee_amount;
+
+        let buy_mint_key = ctx.accounts.buy_mint.key();
+        let seeds = &[
+            constants::seeds::TOKEN_ACCOUNT_SEED,
+            buy_mint_key.as_ref(),
+            &[ctx.accounts.buy_token_info.token_account_bump],
+        ];
+        let signer = [&seeds[..]];
+
+        // Transfer the bought tokens from the second token account to user
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.buy_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.buy_source_token_account.to_account_info(),
+                    mint: ctx.accounts.buy_mint.to_account_info(),
+                    to: ctx.accounts.buy_user_token_account.to_account_info(),
+                    authority: ctx.accounts.buy_source_token_account.to_account_info(),
+                },
+                &signer,
+            ),
+            bought_token_amount,
+            ctx.accounts.buy_mint.decimals,
+        )?;
+
+        ctx.accounts.buy_user_token_account.reload()?;
+        ctx.accounts
+            .buy_token_info
+            .check_holding(ctx.accounts.buy_user_token_account.amount)?;
+
+        let tokens_sold_event = events::TokensSold {
+            token: ctx.accounts.sell_mint.key(),
+            by: ctx.accounts.signer.key(),
+            amount: token_amount,
+        };
+        emit!(tokens_sold_event);
+
+        let tokens_bought_event = events::TokensBought {
+            token: ctx.accounts.buy_mint.key(),
+            by: ctx.accounts.signer.key(),
+            amount: bought_token_amount,
+        };
+        emit!(tokens_bought_event);
+
+        let tokens_swapped_event = events::TokensSwapped {
+            from_token: ctx.accounts.sell_mint.key(),
+            to_token: ctx.accounts.buy_mint.key(),
+            by: ctx.accounts.signer.key(),
+            amount_in: token_amount,
+            amount_out: bought_token_amount,
+        };
+        emit!(tokens_swapped_event);
+
+        Ok(())
+    }
+
+    /// Buys several sol quoted tokens at once. `remaining_accounts` holds the mint, token info,
+    /// token account, user token account and price observations of every token, in the order of `buys`.
+    /// Missing user token accounts are opened, and `token_2022_program` is only needed for Token-2022 mints
+    pub fn buy_tokens_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BuyTokensBatch<'info>>,
+        buys: Vec<BatchBuyParams>,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() == buys.len() * constants::general::BATCH_BUY_ACCOUNTS,
+            errors::CustomErrors::InvalidRemainingAccounts
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let mut platform_fee_amount = 0;
+
+        for (buy, accounts) in buys.iter().zip(
+            ctx.remaining_accounts
+                .chunks_exact(constants::general::BATCH_BUY_ACCOUNTS),
+        ) {
+            let [mint, token_info, source_token_account, user_token_account, price_observations] =
+                accounts
+            else {
+                return err!(errors::CustomErrors::InvalidRemainingAccounts);
+            };
+
+            // Remaining accounts aren't checked by anchor, validate them as `BuyTokens` would
+            // Tokens of both programs can be mixed, each moves through the program owning its mint
+            let token_program = if *mint.owner == ctx.accounts.token_program.key() {
+                ctx.accounts.token_program.to_account_info()
+            } else {
+                match &ctx.accounts.token_2022_program {
+                    Some(token_2022_program) if *mint.owner == token_2022_program.key() => {
+                        token_2022_program.to_account_info()
+                    }
+                    _ => return err!(errors::CustomErrors::InvalidRemainingAccounts),
+                }
+            };
+            let mint_state = InterfaceAccount::<MintInterface>::try_from(mint)?;
+            let mut token_info_state = Account::<TokenInfo>::try_from(token_info)?;
+            let mut price_observations_state =
+                Account::<PriceObservations>::try_from(price_observations)?;
+            require!(
+                utils::is_program_address(
+                    token_info.key,
+                    &[constants::seeds::TOKEN_SEED, mint.key.as_ref()],
+                    token_info_state.bump,
+                ) && utils::is_program_address(
+                    source_token_account.key,
+                    &[constants::seeds::TOKEN_ACCOUNT_SEED, mint.key.as_ref()],
+                    token_info_state.token_account_bump,
+                ) && utils::is_program_address(
+                    price_observations.key,
+                    &[constants::seeds::PRICE_OBSERVATIONS_SEED, mint.key.as_ref()],
+                    price_observations_state.bump,
+                ),
+                errors::CustomErrors::InvalidRemainingAccounts
+            );
+            require_keys_eq!(
+                token_info_state.platform,
+                ctx.accounts.platform.key(),
+                errors::CustomErrors::PlatformMismatch
+            );
+            // Only the signer's associated token account, so the max holding can't be dodged
+            require_keys_eq!(
+                *user_token_account.key,
+                get_associated_token_address_with_program_id(
+                    ctx.accounts.signer.key,
+                    mint.key,
+                    token_program.key,
+                ),
+                errors::CustomErrors::InvalidRemainingAccounts
+            );
+            token_info_state.check_tradable()?;
+            require!(
+                token_info_state.is_native_quote(),
+                errors::CustomErrors::NativeQuoteRequired
+            );
+
+            let fee_in_bps = ctx
+                .accounts
+                .platform
+                .current_fee_in_bps(&token_info_state, now);
+            let fee_amount =
+                utils::calculate_buy_fee(&(buy.sol_amount as u128), &(fee_in_bps as u128));
+            let sol_amount_after_fee = buy.sol_amount - fee_amount;
+            let token_amount = token_info_state.buy(sol_amount_after_fee, now)?;
+            price_observations_state.record(&token_info_state);
+            // The user receives the tokens net of their transfer fee
+            let received_token_amount =
+                token_amount - token_extensions::get_transfer_fee(mint, token_amount)?;
+            require!(
+                received_token_amount >= buy.min_token_amount_out,
+                errors::CustomErrors::SlippageExceeded
+            );
+            platform_fee_amount += fee_amount;
+
+            // Transfer sol amount (after applying fee) from signer to token info account
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.signer.to_account_info(),
+                        to: token_info.clone(),
+                    },
+                ),
+                sol_amount_after_fee,
+            )?;
+
+            let seeds = &[
+                constants::seeds::TOKEN_ACCOUNT_SEED,
+                mint.key.as_ref(),
+                &[token_info_state.token_account_bump],
+            ];
+            let signer = [&seeds[..]];
+
+            if user_token_account.data_is_empty() {
+                associated_token::create(CpiContext::new(
+                    ctx.accounts.associated_token_program.to_account_info(),
+                    associated_token::Create {
+                        payer: ctx.accounts.signer.to_account_info(),
+                        associated_token: user_token_account.clone(),
+                        authority: ctx.accounts.signer.to_account_info(),
+                        mint: mint.clone(),
+                        system_program: ctx.accounts.system_program.to_account_info(),
+                        token_program: token_program.clone(),
+                    },
+                ))?;
+            }
+
+            // Transfer tokens to user
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program,
+                    TransferChecked {
+                        from: source_token_account.clone(),
+                        mint: mint.clone(),
+                        to: user_token_account.clone(),
+                        authority: source_token_account.clone(),
+                    },
+                    &signer,
+                ),
+                token_amount,
+                mint_state.decimals,
+            )?;
+
+            let user_token_account_state =
+                InterfaceAccount::<TokenAccountInterface>::try_from(user_token_account)?;
+            token_info_state.check_holding(user_token_account_state.amount)?;
+
+            let tokens_bought_event = events::TokensBought {
+                token: mint.key(),
+                by: ctx.accounts.signer.key(),
+                amount: token_amount,
+            };
+            emit!(tokens_bought_event);
+
+            // Persist right away, so a token listed twice is bought against its updated curve
+            token_info_state.exit(&crate::ID)?;
+            price_observations_state.exit(&crate::ID)?;
+        }
+
+        ctx.accounts.platform.accumulated_fees += platform_fee_amount;
+
+        // Transfer fees (in sol) of all the buys to the platform account
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.signer.to_account_info(),
+                    to: ctx.accounts.platform.to_account_info(),
+                },
+            ),
+            platform_fee_amount,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn add_liquidity(ctx: Context<AddLiquidity>) -> Result<()> {
+        require!(
+            ctx.accounts.token_info.launched,
             errors::CustomErrors::NotLaunched
         );
 
-        let sol_amount = ctx.accounts.token_info.sol_reserve - ctx.accounts.token_info.virtual_sol;
+        let sol_amount = ctx
+            .accounts
+            .token_info
+            .sol_reserve
+            .checked_sub(ctx.accounts.token_info.virtual_sol)
+            .ok_or(errors::CustomErrors::ReserveBelowVirtualSol)?;
         let token_amount = utils::get_amount_using_spot_price(
             &(sol_amount as u128),
             &(ctx.accounts.token_info.sol_reserve as u128),
             &(ctx.accounts.token_info.token_reserve as u128),
         );
 
+        // Keep the reserves in line with the lamports left on the token info account
+        ctx.accounts.token_info.sol_reserve -= sol_amount;
+        ctx.accounts.token_info.token_reserve -= token_amount;
+
         let mint_token_account_key = ctx.accounts.mint.key();
         let token_transfer_seeds = &[
             constants::seeds::TOKEN_ACCOUNT_SEED,
             mint_token_account_key.as_ref(),
-            &[ctx.bumps.source_token_account],
+            &[ctx.accounts.token_info.token_account_bump],
         ];
         let token_transfer_signer = [&token_transfer_seeds[..]];
 
         // Transfer token amount to admin
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                SplTransfer {
+                TransferChecked {
                     from: ctx.accounts.source_token_account.to_account_info().clone(),
+                    mint: ctx.accounts.mint.to_account_info(),
                     to: ctx.accounts.user_token_account.to_account_info().clone(),
                     authority: ctx.accounts.source_token_account.to_account_info().clone(),
                 },
                 &token_transfer_signer,
             ),
             token_amount,
+            ctx.accounts.mint.decimals,
         )?;
 
-        **ctx
-            .accounts
-            .token_info
-            .to_account_info()
-            .try_borrow_mut_lamports()? -= sol_amount;
-        **ctx
-            .accounts
-            .signer
-            .to_account_info()
-            .try_borrow_mut_lamports()? += sol_amount;
+        if ctx.accounts.token_info.is_native_quote() {
+            **ctx
+                .accounts
+                .token_info
+                .to_account_info()
+                .try_borrow_mut_lamports()? -= sol_amount;
+            **ctx
+                .accounts
+                .signer
+                .to_account_info()
+                .try_borrow_mut_lamports()? += sol_amount;
+        } else {
+            let (
+                Some(quote_mint),
+                Some(user_quote_account),
+                Some(quote_vault),
+                Some(quote_token_program),
+            ) = (
+                &ctx.accounts.quote_mint,
+                &ctx.accounts.user_quote_account,
+                &ctx.accounts.quote_vault,
+                &ctx.accounts.quote_token_program,
+            )
+            else {
+                return err!(errors::CustomErrors::QuoteAccountsMissing);
+            };
+
+            let quote_vault_seeds = &[
+                constants::seeds::QUOTE_VAULT_SEED,
+                mint_token_account_key.as_ref(),
+                &[ctx.accounts.token_info.quote_vault_bump],
+            ];
+            let quote_vault_signer = [&quote_vault_seeds[..]];
+
+            // Transfer quote amount to admin
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    quote_token_program.to_account_info(),
+                    TransferChecked {
+                        from: quote_vault.to_account_info(),
+                        mint: quote_mint.to_account_info(),
+                        to: user_quote_account.to_account_info(),
+                        authority: quote_vault.to_account_info(),
+                    },
+                    &quote_vault_signer,
+                ),
+                sol_amount,
+                quote_mint.decimals,
+            )?;
+        }
 
         let liquidity_added_event = events::LiquidityAdded {
             token: ctx.accounts.mint.key(),
@@ -407,9 +2271,83 @@ pub mod ZkAGI_Contracts {
 // Contexts
 
 #[derive(Accounts)]
+#[instruction(init_params: PlatformInitParams)]
 pub struct Initialize<'info> {
-    #[account(init, payer=signer, seeds=[constants::seeds::PLATFORM_SEED], bump, space=constants::general::DISCRIMINATOR_SIZE + Platform::INIT_SPACE)]
+    #[account(init, payer=signer, seeds=[constants::seeds::PLATFORM_SEED, init_params.id.to_le_bytes().as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + Platform::INIT_SPACE)]
+    pub platform: Account<'info, Platform>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account()]
+    pub quote_mint: Option<Account<'info, Mint>>,
+    #[account(init, payer=signer, seeds=[constants::seeds::FEE_VAULT_SEED, platform.key().as_ref()], bump, token::mint=quote_mint, token::authority=fee_vault)]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct AddAllowedCreator<'info> {
+    #[account(seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump, constraint=platform.owner == signer.key() @ errors::CustomErrors::NotOwner)]
+    pub platform: Account<'info, Platform>,
+    #[account(init, payer=signer, seeds=[constants::seeds::ALLOWED_CREATOR_SEED, platform.key().as_ref(), creator.as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + AllowedCreator::INIT_SPACE)]
+    pub allowed_creator: Account<'info, AllowedCreator>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(value: String)]
+pub struct AddReservation<'info> {
+    #[account(seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump, constraint=platform.owner == signer.key() @ errors::CustomErrors::NotOwner, constraint=platform.timelock_delay == 0 @ errors::CustomErrors::TimelockActive)]
+    pub platform: Account<'info, Platform>,
+    #[account(init, payer=signer, seeds=[constants::seeds::RESERVATION_SEED, platform.key().as_ref(), utils::get_reservation_hash(&value).as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + Reservation::INIT_SPACE)]
+    pub reservation: Account<'info, Reservation>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveReservation<'info> {
+    #[account(seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump, constraint=platform.owner == signer.key() @ errors::CustomErrors::NotOwner, constraint=platform.timelock_delay == 0 @ errors::CustomErrors::TimelockActive)]
+    pub platform: Account<'info, Platform>,
+    #[account(mut, seeds=[constants::seeds::RESERVATION_SEED, platform.key().as_ref(), reservation.hash.as_ref()], bump, close=signer)]
+    pub reservation: Account<'info, Reservation>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveAllowedCreator<'info> {
+    #[account(seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump, constraint=platform.owner == signer.key() @ errors::CustomErrors::NotOwner)]
+    pub platform: Account<'info, Platform>,
+    #[account(mut, seeds=[constants::seeds::ALLOWED_CREATOR_SEED, platform.key().as_ref(), allowed_creator.creator.as_ref()], bump, close=signer)]
+    pub allowed_creator: Account<'info, AllowedCreator>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigOperation<'info> {
+    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump, constraint=platform.owner == signer.key() @ errors::CustomErrors::NotOwner, constraint=platform.timelock_delay == 0 @ errors::CustomErrors::TimelockActive)]
+    pub platform: Account<'info, Platform>,
+    pub signer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QueueConfigChange<'info> {
+    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump, constraint=platform.owner == signer.key() @ errors::CustomErrors::NotOwner)]
     pub platform: Account<'info, Platform>,
+    #[account(init, payer=signer, seeds=[constants::seeds::PENDING_CHANGE_SEED, platform.key().as_ref(), platform.pending_change_nonce.to_le_bytes().as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + PendingChange::INIT_SPACE)]
+    pub pending_change: Account<'info, PendingChange>,
     #[account(mut)]
     pub signer: Signer<'info>,
 
@@ -417,27 +2355,172 @@ pub struct Initialize<'info> {
 }
 
 #[derive(Accounts)]
-pub struct PlatformOperation<'info> {
-    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED], bump, constraint=platform.owner == signer.key() @ errors::CustomErrors::NotOwner)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump)]
     pub platform: Account<'info, Platform>,
+    #[account(mut, seeds=[constants::seeds::PENDING_CHANGE_SEED, platform.key().as_ref(), pending_change.id.to_le_bytes().as_ref()], bump, close=proposer)]
+    pub pending_change: Account<'info, PendingChange>,
+    /// CHECK: Receives the rent of the pending change, checked against the stored proposer
+    #[account(mut, constraint=proposer.key() == pending_change.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(mut)]
     pub signer: Signer<'info>,
 
+    #[account(init, payer=signer, seeds=[constants::seeds::RESERVATION_SEED, platform.key().as_ref(), pending_change.operation.reservation_hash().as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + Reservation::INIT_SPACE)]
+    pub reservation: Option<Account<'info, Reservation>>,
+    #[account(mut, seeds=[constants::seeds::RESERVATION_SEED, platform.key().as_ref(), removed_reservation.hash.as_ref()], bump, close=proposer)]
+    pub removed_reservation: Option<Account<'info, Reservation>>,
+    #[account(mut, seeds=[constants::seeds::TOKEN_SEED, token_info.token.as_ref()], bump=token_info.bump, constraint=token_info.platform == platform.key() @ errors::CustomErrors::PlatformMismatch)]
+    pub token_info: Option<Account<'info, TokenInfo>>,
+
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    #[account(seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump, constraint=platform.owner == signer.key() @ errors::CustomErrors::NotOwner)]
+    pub platform: Account<'info, Platform>,
+    #[account(mut, seeds=[constants::seeds::PENDING_CHANGE_SEED, platform.key().as_ref(), pending_change.id.to_le_bytes().as_ref()], bump, close=proposer)]
+    pub pending_change: Account<'info, PendingChange>,
+    /// CHECK: Receives the rent of the pending change, checked against the stored proposer
+    #[account(mut, constraint=proposer.key() == pending_change.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    pub signer: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct WithdrawFees<'info> {
-    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED], bump, constraint = platform.owner == signer.key())]
+    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump, constraint=platform.owner == signer.key() @ errors::CustomErrors::NotOwner)]
+    pub platform: Account<'info, Platform>,
+    pub signer: Signer<'info>,
+    /// CHECK: Fee destination, checked against the treasury configured on the platform
+    #[account(mut, constraint=treasury.key() == platform.treasury @ errors::CustomErrors::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawQuoteFees<'info> {
+    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump, constraint=platform.owner == signer.key() @ errors::CustomErrors::NotOwner)]
+    pub platform: Account<'info, Platform>,
+    pub signer: Signer<'info>,
+
+    #[account(address=platform.quote_mint)]
+    pub quote_mint: Account<'info, Mint>,
+    #[account(mut, seeds=[constants::seeds::FEE_VAULT_SEED, platform.key().as_ref()], bump=platform.fee_vault_bump, token::mint=platform.quote_mint, token::authority=fee_vault)]
+    pub fee_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint=platform.quote_mint, constraint=treasury_quote_account.owner == platform.treasury @ errors::CustomErrors::InvalidTreasury)]
+    pub treasury_quote_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct AuditPlatform<'info> {
+    #[account(seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump)]
+    pub platform: Account<'info, Platform>,
+    #[account(seeds=[constants::seeds::FEE_VAULT_SEED, platform.key().as_ref()], bump=platform.fee_vault_bump)]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct AuditToken<'info> {
+    #[account(mut, seeds=[constants::seeds::TOKEN_SEED, token_info.token.as_ref()], bump=token_info.bump)]
+    pub token_info: Account<'info, TokenInfo>,
+    #[account(seeds=[constants::seeds::QUOTE_VAULT_SEED, token_info.token.as_ref()], bump=token_info.quote_vault_bump)]
+    pub quote_vault: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct ResumeToken<'info> {
+    #[account(seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump, constraint=platform.owner == signer.key() @ errors::CustomErrors::NotOwner, constraint=platform.timelock_delay == 0 @ errors::CustomErrors::TimelockActive)]
     pub platform: Account<'info, Platform>,
     pub signer: Signer<'info>,
 
+    #[account(mut, seeds=[constants::seeds::TOKEN_SEED, token_info.token.as_ref()], bump=token_info.bump, constraint=token_info.platform == platform.key() @ errors::CustomErrors::PlatformMismatch)]
+    pub token_info: Account<'info, TokenInfo>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePlatform<'info> {
+    /// CHECK: Deserialized manually since its layout may predate the current `Platform`,
+    /// only the legacy singleton platform can have such a layout
+    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED], bump, owner=crate::ID)]
+    pub platform: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct MigrateTokenInfo<'info> {
+    /// CHECK: Deserialized manually since its layout predates the current `TokenInfo`,
+    /// legacy token infos were derived from the token name
+    #[account(mut, seeds=[constants::seeds::TOKEN_SEED, name.as_bytes()], bump, owner=crate::ID)]
+    pub legacy_token_info: UncheckedAccount<'info>,
+    #[account(seeds=[constants::seeds::MINT_SEED, name.as_bytes()], bump)]
+    pub mint: Account<'info, Mint>,
+    #[account(init, payer=signer, seeds=[constants::seeds::TOKEN_SEED, mint.key().as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + TokenInfo::INIT_SPACE)]
+    pub token_info: Box<Account<'info, TokenInfo>>,
+    #[account(init, payer=signer, seeds=[constants::seeds::PRICE_OBSERVATIONS_SEED, mint.key().as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + PriceObservations::INIT_SPACE)]
+    pub price_observations: Box<Account<'info, PriceObservations>>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePlatformCollection<'info> {
+    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump, constraint=platform.owner == signer.key() @ errors::CustomErrors::NotOwner, constraint=platform.collection_mint == Pubkey::default() @ errors::CustomErrors::CollectionAlreadyCreated)]
+    pub platform: Box<Account<'info, Platform>>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(init, payer=signer, seeds=[constants::seeds::COLLECTION_SEED, platform.key().as_ref()], bump, mint::decimals=0, mint::authority=platform, mint::freeze_authority=platform)]
+    pub collection_mint: Box<Account<'info, Mint>>,
+    #[account(init, payer=signer, seeds=[constants::seeds::COLLECTION_ACCOUNT_SEED, platform.key().as_ref()], bump, token::mint=collection_mint, token::authority=platform)]
+    pub collection_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: New Metaplex Account being created
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+    /// CHECK: New Metaplex Account being created
+    #[account(mut)]
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub token_metadata_program: Program<'info, Metaplex>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(init, payer=signer, seeds=[constants::seeds::REFERRAL_SEED, signer.key().as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + Referral::INIT_SPACE)]
+    pub referral: Account<'info, Referral>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralFees<'info> {
+    #[account(mut, seeds=[constants::seeds::REFERRAL_SEED, signer.key().as_ref()], bump=referral.bump, constraint=referral.referrer == signer.key() @ errors::CustomErrors::NotReferrer)]
+    pub referral: Account<'info, Referral>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 #[instruction(create_token_params: CreateTokenParams)]
 pub struct CreateToken<'info> {
-    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED], bump)]
+    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump)]
     pub platform: Box<Account<'info, Platform>>,
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -445,12 +2528,40 @@ pub struct CreateToken<'info> {
     /// CHECK: New Metaplex Account being created
     #[account(mut)]
     pub metadata: UncheckedAccount<'info>,
-    #[account(init, payer=signer, seeds=[constants::seeds::MINT_SEED, create_token_params.name.as_bytes()], bump, mint::decimals=constants::general::DECIMALS, mint::authority=mint)]
+    #[account(init, payer=signer, seeds=[constants::seeds::MINT_SEED, platform.key().as_ref(), create_token_params.name.as_bytes()], bump, mint::decimals=constants::general::DECIMALS, mint::authority=mint)]
     pub mint: Box<Account<'info, Mint>>,
     #[account(init, payer=signer, seeds=[constants::seeds::TOKEN_ACCOUNT_SEED, mint.key().as_ref()], bump, token::mint=mint, token::authority=token_account)]
     pub token_account: Box<Account<'info, TokenAccount>>,
-    #[account(init, payer=signer, seeds=[constants::seeds::TOKEN_SEED, create_token_params.name.as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + TokenInfo::INIT_SPACE,)]
+    #[account(init, payer=signer, seeds=[constants::seeds::TOKEN_SEED, mint.key().as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + TokenInfo::INIT_SPACE)]
     pub token_info: Box<Account<'info, TokenInfo>>,
+    #[account(init, payer=signer, seeds=[constants::seeds::PRICE_OBSERVATIONS_SEED, mint.key().as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + PriceObservations::INIT_SPACE)]
+    pub price_observations: Box<Account<'info, PriceObservations>>,
+    #[account(init, payer=signer, seeds=[constants::seeds::TOKEN_PROFILE_SEED, mint.key().as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + TokenProfile::INIT_SPACE)]
+    pub token_profile: Option<Box<Account<'info, TokenProfile>>>,
+    #[account(init, payer=signer, seeds=[constants::seeds::VESTING_SEED, mint.key().as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + Vesting::INIT_SPACE)]
+    pub vesting: Option<Box<Account<'info, Vesting>>>,
+    #[account(init, payer=signer, seeds=[constants::seeds::VESTING_ACCOUNT_SEED, mint.key().as_ref()], bump, token::mint=mint, token::authority=vesting_token_account)]
+    pub vesting_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(seeds=[constants::seeds::ALLOWED_CREATOR_SEED, platform.key().as_ref(), signer.key().as_ref()], bump)]
+    pub allowed_creator: Option<Box<Account<'info, AllowedCreator>>>,
+    /// CHECK: Reservation of the token name, if any, checked in the instruction
+    #[account(seeds=[constants::seeds::RESERVATION_SEED, platform.key().as_ref(), utils::get_reservation_hash(&create_token_params.name).as_ref()], bump)]
+    pub name_reservation: UncheckedAccount<'info>,
+    /// CHECK: Reservation of the token symbol, if any, checked in the instruction
+    #[account(seeds=[constants::seeds::RESERVATION_SEED, platform.key().as_ref(), utils::get_reservation_hash(&create_token_params.symbol).as_ref()], bump)]
+    pub symbol_reservation: UncheckedAccount<'info>,
+    #[account(address=platform.quote_mint)]
+    pub quote_mint: Option<Box<Account<'info, Mint>>>,
+    #[account(init, payer=signer, seeds=[constants::seeds::QUOTE_VAULT_SEED, mint.key().as_ref()], bump, token::mint=quote_mint, token::authority=quote_vault)]
+    pub quote_vault: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(address=platform.collection_mint)]
+    pub collection_mint: Option<Box<Account<'info, Mint>>>,
+    /// CHECK: Validated by Metaplex against the collection mint
+    #[account(mut)]
+    pub collection_metadata: Option<UncheckedAccount<'info>>,
+    /// CHECK: Validated by Metaplex against the collection mint
+    #[account()]
+    pub collection_master_edition: Option<UncheckedAccount<'info>>,
 
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
@@ -459,80 +2570,263 @@ pub struct CreateToken<'info> {
 }
 
 #[derive(Accounts)]
-pub struct InitAccountsForToken<'info> {
+#[instruction(create_token_params: CreateTokenParams)]
+pub struct CreateToken2022<'info> {
+    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump)]
+    pub platform: Box<Account<'info, Platform>>,
     #[account(mut)]
     pub signer: Signer<'info>,
 
-    #[account()]
+    /// CHECK: New Token-2022 mint being created, sized for its extensions
+    #[account(mut, seeds=[constants::seeds::MINT_SEED, platform.key().as_ref(), create_token_params.name.as_bytes()], bump)]
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: New Token-2022 token account being created, sized for the mint extensions
+    #[account(mut, seeds=[constants::seeds::TOKEN_ACCOUNT_SEED, mint.key().as_ref()], bump)]
+    pub token_account: UncheckedAccount<'info>,
+    #[account(init, payer=signer, seeds=[constants::seeds::TOKEN_SEED, mint.key().as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + TokenInfo::INIT_SPACE)]
+    pub token_info: Box<Account<'info, TokenInfo>>,
+    #[account(init, payer=signer, seeds=[constants::seeds::PRICE_OBSERVATIONS_SEED, mint.key().as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + PriceObservations::INIT_SPACE)]
+    pub price_observations: Box<Account<'info, PriceObservations>>,
+    #[account(init, payer=signer, seeds=[constants::seeds::TOKEN_PROFILE_SEED, mint.key().as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + TokenProfile::INIT_SPACE)]
+    pub token_profile: Option<Box<Account<'info, TokenProfile>>>,
+    #[account(init, payer=signer, seeds=[constants::seeds::VESTING_SEED, mint.key().as_ref()], bump, space=constants::general::DISCRIMINATOR_SIZE + Vesting::INIT_SPACE)]
+    pub vesting: Option<Box<Account<'info, Vesting>>>,
+    /// CHECK: New Token-2022 token account being created, sized for the mint extensions
+    #[account(mut, seeds=[constants::seeds::VESTING_ACCOUNT_SEED, mint.key().as_ref()], bump)]
+    pub vesting_token_account: Option<UncheckedAccount<'info>>,
+    #[account(seeds=[constants::seeds::ALLOWED_CREATOR_SEED, platform.key().as_ref(), signer.key().as_ref()], bump)]
+    pub allowed_creator: Option<Box<Account<'info, AllowedCreator>>>,
+    /// CHECK: Reservation of the token name, if any, checked in the instruction
+    #[account(seeds=[constants::seeds::RESERVATION_SEED, platform.key().as_ref(), utils::get_reservation_hash(&create_token_params.name).as_ref()], bump)]
+    pub name_reservation: UncheckedAccount<'info>,
+    /// CHECK: Reservation of the token symbol, if any, checked in the instruction
+    #[account(seeds=[constants::seeds::RESERVATION_SEED, platform.key().as_ref(), utils::get_reservation_hash(&create_token_params.symbol).as_ref()], bump)]
+    pub symbol_reservation: UncheckedAccount<'info>,
+    #[account(address=platform.quote_mint)]
+    pub quote_mint: Option<Box<Account<'info, Mint>>>,
+    #[account(init, payer=signer, seeds=[constants::seeds::QUOTE_VAULT_SEED, mint.key().as_ref()], bump, token::mint=quote_mint, token::authority=quote_vault, token::token_program=quote_token_program)]
+    pub quote_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+    pub quote_token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+pub struct TokenMetadataOperation<'info> {
+    pub signer: Signer<'info>,
+
     pub mint: Account<'info, Mint>,
-    #[account(init, payer=signer, seeds=[constants::seeds::TOKEN_ACCOUNT_SEED, signer.key().as_ref(), mint.key().as_ref()], bump,  token::mint=mint, token::authority=signer)]
-    pub token_account: Account<'info, TokenAccount>,
+    #[account(seeds=[constants::seeds::TOKEN_SEED, mint.key().as_ref()], bump=token_info.bump, constraint=token_info.creator == signer.key() @ errors::CustomErrors::NotCreator)]
+    pub token_info: Account<'info, TokenInfo>,
+    #[account(mut, seeds=[constants::seeds::METADATA_SEED, token_metadata_program.key().as_ref(), mint.key().as_ref()], seeds::program=token_metadata_program.key(), bump, constraint=metadata.update_authority == mint.key() @ errors::CustomErrors::MetadataNotProgramManaged)]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    pub token_metadata_program: Program<'info, Metaplex>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTokenProfile<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(seeds=[constants::seeds::TOKEN_SEED, token_info.token.as_ref()], bump=token_info.bump, constraint=token_info.creator == signer.key() @ errors::CustomErrors::NotCreator, constraint=!token_info.launched @ errors::CustomErrors::AlreadyLaunched)]
+    pub token_info: Account<'info, TokenInfo>,
+    #[account(mut, seeds=[constants::seeds::TOKEN_PROFILE_SEED, token_info.token.as_ref()], bump)]
+    pub token_profile: Account<'info, TokenProfile>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mint::token_program=token_program)]
+    pub mint: InterfaceAccount<'info, MintInterface>,
+    #[account(seeds=[constants::seeds::TOKEN_SEED, mint.key().as_ref()], bump=token_info.bump)]
+    pub token_info: Account<'info, TokenInfo>,
+    #[account(mut, seeds=[constants::seeds::VESTING_SEED, mint.key().as_ref()], bump, constraint=vesting.beneficiary == signer.key() @ errors::CustomErrors::NotVestingBeneficiary)]
+    pub vesting: Account<'info, Vesting>,
+    #[account(mut, seeds=[constants::seeds::VESTING_ACCOUNT_SEED, mint.key().as_ref()], bump, token::mint=mint, token::authority=vesting_token_account, token::token_program=token_program)]
+    pub vesting_token_account: InterfaceAccount<'info, TokenAccountInterface>,
+    #[account(mut, associated_token::mint=mint, associated_token::authority=signer, associated_token::token_program=token_program)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccountInterface>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct InitAccountsForToken<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mint::token_program=token_program)]
+    pub mint: InterfaceAccount<'info, MintInterface>,
+    #[account(init_if_needed, payer=signer, associated_token::mint=mint, associated_token::authority=signer, associated_token::token_program=token_program)]
+    pub token_account: InterfaceAccount<'info, TokenAccountInterface>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 #[derive(Accounts)]
 pub struct BuyTokens<'info> {
-    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED], bump)]
+    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump)]
+    pub platform: Account<'info, Platform>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mint::token_program=token_program)]
+    pub mint: InterfaceAccount<'info, MintInterface>,
+    #[account(mut, seeds=[constants::seeds::TOKEN_SEED, mint.key().as_ref()], bump=token_info.bump, constraint=token_info.platform == platform.key() @ errors::CustomErrors::PlatformMismatch)]
+    pub token_info: Account<'info, TokenInfo>,
+    #[account(mut, seeds=[constants::seeds::TOKEN_ACCOUNT_SEED, mint.key().as_ref()], bump=token_info.token_account_bump, token::mint=mint, token::authority=source_token_account, token::token_program=token_program)]
+    pub source_token_account: InterfaceAccount<'info, TokenAccountInterface>,
+    #[account(mut, seeds=[constants::seeds::PRICE_OBSERVATIONS_SEED, mint.key().as_ref()], bump=price_observations.bump)]
+    pub price_observations: Box<Account<'info, PriceObservations>>,
+    #[account(init_if_needed, payer=signer, associated_token::mint=mint, associated_token::authority=signer, associated_token::token_program=token_program)]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccountInterface>>,
+    #[account(mut, seeds=[constants::seeds::REFERRAL_SEED, referral.referrer.as_ref()], bump=referral.bump, constraint=referral.referrer != signer.key() @ errors::CustomErrors::SelfReferral)]
+    pub referral: Option<Box<Account<'info, Referral>>>,
+    #[account(address=token_info.quote_mint)]
+    pub quote_mint: Option<Box<Account<'info, Mint>>>,
+    #[account(mut, token::mint=token_info.quote_mint, token::authority=signer)]
+    pub user_quote_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut, seeds=[constants::seeds::QUOTE_VAULT_SEED, mint.key().as_ref()], bump=token_info.quote_vault_bump)]
+    pub quote_vault: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut, seeds=[constants::seeds::FEE_VAULT_SEED, platform.key().as_ref()], bump=platform.fee_vault_bump)]
+    pub fee_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub quote_token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+pub struct SellTokens<'info> {
+    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump)]
+    pub platform: Account<'info, Platform>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mint::token_program=token_program)]
+    pub mint: Box<InterfaceAccount<'info, MintInterface>>,
+    #[account(mut, seeds=[constants::seeds::TOKEN_SEED, mint.key().as_ref()], bump=token_info.bump, constraint=token_info.platform == platform.key() @ errors::CustomErrors::PlatformMismatch)]
+    pub token_info: Box<Account<'info, TokenInfo>>,
+    #[account(mut, seeds=[constants::seeds::TOKEN_ACCOUNT_SEED, mint.key().as_ref()], bump=token_info.token_account_bump, token::mint=mint, token::authority=source_token_account, token::token_program=token_program)]
+    pub source_token_account: Box<InterfaceAccount<'info, TokenAccountInterface>>,
+    #[account(mut, seeds=[constants::seeds::PRICE_OBSERVATIONS_SEED, mint.key().as_ref()], bump=price_observations.bump)]
+    pub price_observations: Box<Account<'info, PriceObservations>>,
+    #[account(mut, associated_token::mint=mint, associated_token::authority=signer, associated_token::token_program=token_program)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccountInterface>,
+    #[account(mut, seeds=[constants::seeds::REFERRAL_SEED, referral.referrer.as_ref()], bump=referral.bump, constraint=referral.referrer != signer.key() @ errors::CustomErrors::SelfReferral)]
+    pub referral: Option<Box<Account<'info, Referral>>>,
+    #[account(address=token_info.quote_mint)]
+    pub quote_mint: Option<Box<Account<'info, Mint>>>,
+    #[account(mut, token::mint=token_info.quote_mint, token::authority=signer)]
+    pub user_quote_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut, seeds=[constants::seeds::QUOTE_VAULT_SEED, mint.key().as_ref()], bump=token_info.quote_vault_bump)]
+    pub quote_vault: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut, seeds=[constants::seeds::FEE_VAULT_SEED, platform.key().as_ref()], bump=platform.fee_vault_bump)]
+    pub fee_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteTrade<'info> {
+    #[account(seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump)]
     pub platform: Account<'info, Platform>,
+
+    pub mint: InterfaceAccount<'info, MintInterface>,
+    #[account(seeds=[constants::seeds::TOKEN_SEED, mint.key().as_ref()], bump=token_info.bump, constraint=token_info.platform == platform.key() @ errors::CustomErrors::PlatformMismatch)]
+    pub token_info: Account<'info, TokenInfo>,
+}
+
+#[derive(Accounts)]
+pub struct ObservePrice<'info> {
+    #[account(mut, seeds=[constants::seeds::TOKEN_SEED, token_info.token.as_ref()], bump=token_info.bump)]
+    pub token_info: Account<'info, TokenInfo>,
+    #[account(mut, seeds=[constants::seeds::PRICE_OBSERVATIONS_SEED, token_info.token.as_ref()], bump=price_observations.bump)]
+    pub price_observations: Box<Account<'info, PriceObservations>>,
+}
+
+#[derive(Accounts)]
+pub struct SwapTokens<'info> {
+    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump)]
+    pub platform: Box<Account<'info, Platform>>,
     #[account(mut)]
     pub signer: Signer<'info>,
 
-    #[account()]
-    pub mint: Account<'info, Mint>,
-    #[account(mut, constraint=mint.key() == token_info.token)]
-    pub token_info: Account<'info, TokenInfo>,
-    #[account(mut, seeds=[constants::seeds::TOKEN_ACCOUNT_SEED, mint.key().as_ref()], bump, token::mint=mint, token::authority=source_token_account)]
-    pub source_token_account: Account<'info, TokenAccount>,
-    #[account(mut, associated_token::mint=mint, associated_token::authority=signer)]
-    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(mint::token_program=sell_token_program)]
+    pub sell_mint: Box<InterfaceAccount<'info, MintInterface>>,
+    #[account(mut, seeds=[constants::seeds::TOKEN_SEED, sell_mint.key().as_ref()], bump=sell_token_info.bump, constraint=sell_token_info.platform == platform.key() @ errors::CustomErrors::PlatformMismatch)]
+    pub sell_token_info: Box<Account<'info, TokenInfo>>,
+    #[account(mut, seeds=[constants::seeds::TOKEN_ACCOUNT_SEED, sell_mint.key().as_ref()], bump=sell_token_info.token_account_bump, token::mint=sell_mint, token::authority=sell_source_token_account, token::token_program=sell_token_program)]
+    pub sell_source_token_account: Box<InterfaceAccount<'info, TokenAccountInterface>>,
+    #[account(mut, seeds=[constants::seeds::PRICE_OBSERVATIONS_SEED, sell_mint.key().as_ref()], bump=sell_price_observations.bump)]
+    pub sell_price_observations: Box<Account<'info, PriceObservations>>,
+    #[account(mut, associated_token::mint=sell_mint, associated_token::authority=signer, associated_token::token_program=sell_token_program)]
+    pub sell_user_token_account: Box<InterfaceAccount<'info, TokenAccountInterface>>,
+
+    #[account(mint::token_program=buy_token_program, constraint=buy_mint.key() != sell_mint.key() @ errors::CustomErrors::SameToken)]
+    pub buy_mint: Box<InterfaceAccount<'info, MintInterface>>,
+    #[account(mut, seeds=[constants::seeds::TOKEN_SEED, buy_mint.key().as_ref()], bump=buy_token_info.bump, constraint=buy_token_info.platform == platform.key() @ errors::CustomErrors::PlatformMismatch)]
+    pub buy_token_info: Box<Account<'info, TokenInfo>>,
+    #[account(mut, seeds=[constants::seeds::TOKEN_ACCOUNT_SEED, buy_mint.key().as_ref()], bump=buy_token_info.token_account_bump, token::mint=buy_mint, token::authority=buy_source_token_account, token::token_program=buy_token_program)]
+    pub buy_source_token_account: Box<InterfaceAccount<'info, TokenAccountInterface>>,
+    #[account(mut, seeds=[constants::seeds::PRICE_OBSERVATIONS_SEED, buy_mint.key().as_ref()], bump=buy_price_observations.bump)]
+    pub buy_price_observations: Box<Account<'info, PriceObservations>>,
+    #[account(init_if_needed, payer=signer, associated_token::mint=buy_mint, associated_token::authority=signer, associated_token::token_program=buy_token_program)]
+    pub buy_user_token_account: Box<InterfaceAccount<'info, TokenAccountInterface>>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub sell_token_program: Interface<'info, TokenInterface>,
+    pub buy_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 #[derive(Accounts)]
-#[instruction(token_amount: u64, name: String)]
-pub struct SellTokens<'info> {
-    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED], bump)]
+pub struct BuyTokensBatch<'info> {
+    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump)]
     pub platform: Account<'info, Platform>,
     #[account(mut)]
     pub signer: Signer<'info>,
 
-    #[account()]
-    pub mint: Box<Account<'info, Mint>>,
-    #[account(mut, seeds=[constants::seeds::TOKEN_SEED, name.as_bytes()], bump, constraint=mint.key() == token_info.token)]
-    pub token_info: Box<Account<'info, TokenInfo>>,
-    #[account(mut, seeds=[constants::seeds::TOKEN_ACCOUNT_SEED, mint.key().as_ref()], bump, token::mint=mint, token::authority=source_token_account)]
-    pub source_token_account: Box<Account<'info, TokenAccount>>,
-    #[account(mut, associated_token::mint=mint, associated_token::authority=signer)]
-    pub user_token_account: Account<'info, TokenAccount>,
-
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 #[derive(Accounts)]
-#[instruction(name: String)]
 pub struct AddLiquidity<'info> {
-    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED], bump)]
+    #[account(mut, seeds=[constants::seeds::PLATFORM_SEED, platform.id_seed().as_ref()], bump=platform.bump)]
     pub platform: Account<'info, Platform>,
     #[account(mut, constraint=signer.key() == platform.owner)]
     pub signer: Signer<'info>,
 
-    #[account()]
-    pub mint: Account<'info, Mint>,
-    #[account(mut, seeds=[constants::seeds::TOKEN_SEED, name.as_ref()], bump, constraint=mint.key() == token_info.token)]
+    #[account(mint::token_program=token_program)]
+    pub mint: InterfaceAccount<'info, MintInterface>,
+    #[account(mut, seeds=[constants::seeds::TOKEN_SEED, mint.key().as_ref()], bump=token_info.bump, constraint=token_info.platform == platform.key() @ errors::CustomErrors::PlatformMismatch)]
     pub token_info: Account<'info, TokenInfo>,
-    #[account(mut, seeds=[constants::seeds::TOKEN_ACCOUNT_SEED, mint.key().as_ref()], bump,  token::mint=mint, token::authority=source_token_account)]
-    pub source_token_account: Account<'info, TokenAccount>,
-    #[account(mut, associated_token::mint=mint, associated_token::authority=signer)]
-    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds=[constants::seeds::TOKEN_ACCOUNT_SEED, mint.key().as_ref()], bump=token_info.token_account_bump,  token::mint=mint, token::authority=source_token_account, token::token_program=token_program)]
+    pub source_token_account: InterfaceAccount<'info, TokenAccountInterface>,
+    #[account(mut, associated_token::mint=mint, associated_token::authority=signer, associated_token::token_program=token_program)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccountInterface>,
+    #[account(address=token_info.quote_mint)]
+    pub quote_mint: Option<Box<Account<'info, Mint>>>,
+    #[account(mut, token::mint=token_info.quote_mint, token::authority=signer)]
+    pub user_quote_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut, seeds=[constants::seeds::QUOTE_VAULT_SEED, mint.key().as_ref()], bump=token_info.quote_vault_bump)]
+    pub quote_vault: Option<Box<Account<'info, TokenAccount>>>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Option<Program<'info, Token>>,
 }
 
 // Accounts
@@ -540,36 +2834,683 @@ pub struct AddLiquidity<'info> {
 #[account]
 #[derive(InitSpace)]
 pub struct Platform {
+    pub version: u8,
+    pub id: u64,
     pub owner: Pubkey,
+    pub treasury: Pubkey,
     pub fee_in_bps: u64,
+    pub fee_schedule: FeeSchedule,
+    pub referral_fee_in_bps: u64,
     pub accumulated_fees: u64,
     pub total_supply: u64,
     pub virtual_sol: u64,
     pub target_pool_balance: u64,
+    /// Bumped by every applied config change, tokens snapshot it at launch
+    pub config_version: u64,
+    pub timelock_delay: i64,
+    pub pending_change_nonce: u64,
+    pub launch_mode: LaunchMode,
+    /// Charged in native sol, only platforms quoted in native sol can set one
+    pub creation_fee: u64,
+    pub creation_fee_waived_for_allowlisted: bool,
+    /// Mint the curves of this platform are quoted in, the default pubkey stands for native sol
+    pub quote_mint: Pubkey,
+    pub accumulated_quote_fees: u64,
+    /// Metaplex collection every token launched here is verified into, the default pubkey if none
+    pub collection_mint: Pubkey,
+    pub bump: u8,
+    /// Bump of the quote fee vault, zero for platforms quoted in native sol
+    pub fee_vault_bump: u8,
+}
+
+impl Platform {
+    /// Checks the config invariants every instruction changing the platform params must uphold
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.owner != Pubkey::default(),
+            errors::CustomErrors::InvalidOwner
+        );
+        require!(
+            self.treasury != Pubkey::default(),
+            errors::CustomErrors::InvalidTreasury
+        );
+        require!(
+            self.fee_in_bps <= constants::general::MAX_ALLOWED_FEE_IN_BPS,
+            errors::CustomErrors::ExcessiveFees
+        );
+        require!(
+            self.fee_schedule.tiers.len() <= MAX_FEE_TIERS
+                && self
+                    .fee_schedule
+                    .tiers
+                    .windows(2)
+                    .all(|tiers| tiers[0].threshold < tiers[1].threshold),
+            errors::CustomErrors::InvalidFeeSchedule
+        );
+        require!(
+            self.fee_schedule
+                .tiers
+                .iter()
+                .all(|tier| tier.fee_in_bps <= constants::general::MAX_ALLOWED_FEE_IN_BPS),
+            errors::CustomErrors::ExcessiveFees
+        );
+        require!(
+            self.referral_fee_in_bps <= constants::general::BPS as u64,
+            errors::CustomErrors::ExcessiveReferralFees
+        );
+        require!(
+            (0..=constants::general::MAX_TIMELOCK_DELAY).contains(&self.timelock_delay),
+            errors::CustomErrors::InvalidTimelockDelay
+        );
+        require!(self.total_supply > 0, errors::CustomErrors::ZeroTotalSupply);
+        require!(self.virtual_sol > 0, errors::CustomErrors::ZeroVirtualSol);
+        require!(
+            self.target_pool_balance > self.virtual_sol,
+            errors::CustomErrors::TargetPoolBalanceBelowVirtualSol
+        );
+        // Creation fees are collected in sol, spl quoted platforms launch for free
+        require!(
+            self.quote_mint == Pubkey::default() || self.creation_fee == 0,
+            errors::CustomErrors::CreationFeeRequiresNativeQuote
+        );
+
+        Ok(())
+    }
+
+    pub fn apply_config(&mut self, update_config_params: UpdateConfigParams) {
+        if let Some(fee_in_bps) = update_config_params.fee_in_bps {
+            self.fee_in_bps = fee_in_bps;
+        }
+        if let Some(fee_schedule) = update_config_params.fee_schedule {
+            self.fee_schedule = fee_schedule;
+        }
+        if let Some(referral_fee_in_bps) = update_config_params.referral_fee_in_bps {
+            self.referral_fee_in_bps = referral_fee_in_bps;
+        }
+        if let Some(total_supply) = update_config_params.total_supply {
+            self.total_supply = total_supply;
+        }
+        if let Some(virtual_sol) = update_config_params.virtual_sol {
+            self.virtual_sol = virtual_sol;
+        }
+        if let Some(target_pool_balance) = update_config_params.target_pool_balance {
+            self.target_pool_balance = target_pool_balance;
+        }
+        if let Some(timelock_delay) = update_config_params.timelock_delay {
+            self.timelock_delay = timelock_delay;
+        }
+        if let Some(creation_fee) = update_config_params.creation_fee {
+            self.creation_fee = creation_fee;
+        }
+        if let Some(creation_fee_waived_for_allowlisted) =
+            update_config_params.creation_fee_waived_for_allowlisted
+        {
+            self.creation_fee_waived_for_allowlisted = creation_fee_waived_for_allowlisted;
+        }
+        if let Some(owner) = update_config_params.owner {
+            self.owner = owner;
+        }
+        if let Some(treasury) = update_config_params.treasury {
+            self.treasury = treasury;
+        }
+        if let Some(launch_mode) = update_config_params.launch_mode {
+            self.launch_mode = launch_mode;
+        }
+    }
+
+    pub fn config(&self) -> PlatformConfig {
+        PlatformConfig {
+            fee_in_bps: self.fee_in_bps,
+            fee_schedule: self.fee_schedule.clone(),
+            referral_fee_in_bps: self.referral_fee_in_bps,
+            total_supply: self.total_supply,
+            virtual_sol: self.virtual_sol,
+            target_pool_balance: self.target_pool_balance,
+            timelock_delay: self.timelock_delay,
+            creation_fee: self.creation_fee,
+            creation_fee_waived_for_allowlisted: self.creation_fee_waived_for_allowlisted,
+            owner: self.owner,
+            treasury: self.treasury,
+            launch_mode: self.launch_mode,
+        }
+    }
+
+    /// Returns whether `creator` may launch tokens under the current launch mode
+    pub fn is_creator_allowed(&self, creator: &Pubkey, allowlisted: bool) -> bool {
+        match self.launch_mode {
+            LaunchMode::Open => true,
+            LaunchMode::Allowlisted => allowlisted,
+            LaunchMode::AdminOnly => *creator == self.owner,
+        }
+    }
+
+    pub fn creation_fee_for(&self, allowlisted: bool) -> u64 {
+        if self.creation_fee_waived_for_allowlisted && allowlisted {
+            0
+        } else {
+            self.creation_fee
+        }
+    }
+
+    /// Returns whether this is the legacy singleton platform derived without an id
+    pub fn is_legacy(&self) -> bool {
+        self.id == constants::general::LEGACY_PLATFORM_ID
+    }
+
+    /// Returns the id seed of the platform address. It is empty for the legacy singleton,
+    /// an empty seed leaves the derived address unchanged
+    pub fn id_seed(&self) -> Vec<u8> {
+        if self.is_legacy() {
+            vec![]
+        } else {
+            self.id.to_le_bytes().to_vec()
+        }
+    }
+
+    pub fn current_fee_in_bps(&self, token_info: &TokenInfo, now: i64) -> u64 {
+        utils::get_fee_in_bps(
+            &self.fee_schedule,
+            self.fee_in_bps,
+            token_info.sol_reserve,
+            token_info.target_pool_balance,
+            now - token_info.created_at,
+        )
+    }
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Debug, Clone, Copy, PartialEq, Eq, Default,
+)]
+pub enum LaunchMode {
+    #[default]
+    Open,
+    Allowlisted,
+    AdminOnly,
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Debug, Clone, Copy, PartialEq, Eq, Default,
+)]
+pub enum MetadataAuthority {
+    /// Metadata can never be changed
+    #[default]
+    Immutable,
+    /// Metadata stays mutable with the creator as update authority
+    Creator,
+    /// Metadata stays mutable with the mint as update authority, so the creator can update
+    /// it through the program until it is locked
+    Program,
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Debug, Clone, Copy, PartialEq, Eq, Default,
+)]
+pub enum FeeScheduleKind {
+    #[default]
+    Flat,
+    CurveProgress,
+    TimeSinceLaunch,
+}
+
+/// Fee applied once the schedule position reaches `threshold`
+/// (bips of the target pool balance, or seconds since launch)
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Debug, Clone)]
+pub struct FeeTier {
+    pub threshold: u64,
+    pub fee_in_bps: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Debug, Clone, Default)]
+pub struct FeeSchedule {
+    pub kind: FeeScheduleKind,
+    #[max_len(MAX_FEE_TIERS)]
+    pub tiers: Vec<FeeTier>,
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct TokenInfo {
+    pub version: u8,
+    pub platform: Pubkey,
     pub token: Pubkey,
     pub creator: Pubkey,
     pub total_supply: u64,
+    pub creator_allocation: u64,
     pub virtual_sol: u64,
     pub sol_reserve: u64,
     pub token_reserve: u64,
     pub target_pool_balance: u64,
+    pub config_version: u64,
+    pub created_at: i64,
     pub launched: bool,
+    pub halted: bool,
+    /// Mint the curve is quoted in, the default pubkey stands for native sol.
+    /// The `*_sol` and `sol_reserve` amounts are denominated in this mint.
+    pub quote_mint: Pubkey,
+    pub bump: u8,
+    pub token_account_bump: u8,
+    /// Bump of the quote vault, zero for tokens quoted in native sol
+    pub quote_vault_bump: u8,
+    /// Running sum of the Q64.64 curve price weighted by the seconds it was held, wraps on overflow
+    pub price_cumulative: u128,
+    pub last_price_update: i64,
+    /// Share of the total supply a single wallet may hold from curve buys, uncapped if none
+    pub max_holding_bps: Option<u64>,
+}
+
+impl TokenInfo {
+    /// Snapshots the platform config into a freshly created token campaign
+    pub fn initialize(
+        &mut self,
+        platform: &Account<Platform>,
+        token: Pubkey,
+        creator: Pubkey,
+        creator_allocation: u64,
+        now: i64,
+        bumps: TokenInfoBumps,
+    ) {
+        self.version = constants::general::TOKEN_INFO_VERSION;
+        self.platform = platform.key();
+        self.quote_mint = platform.quote_mint;
+        self.token = token;
+        self.creator = creator;
+        self.total_supply = platform.total_supply;
+        self.creator_allocation = creator_allocation;
+        self.virtual_sol = platform.virtual_sol;
+        self.sol_reserve = self.virtual_sol;
+        self.token_reserve = self.total_supply - creator_allocation;
+        self.target_pool_balance = platform.target_pool_balance;
+        self.config_version = platform.config_version;
+        self.created_at = now;
+        self.last_price_update = now;
+        self.bump = bumps.token_info;
+        self.token_account_bump = bumps.token_account;
+        self.quote_vault_bump = bumps.quote_vault;
+    }
+
+    pub fn is_native_quote(&self) -> bool {
+        self.quote_mint == Pubkey::default()
+    }
+
+    /// Fails if `balance`, the buyer's balance after a curve buy, exceeds the max holding.
+    /// Only curve buys are capped, so the cap is lifted once the token launches
+    pub fn check_holding(&self, balance: u64) -> Result<()> {
+        let Some(max_holding_bps) = self.max_holding_bps else {
+            return Ok(());
+        };
+
+        require!(
+            balance
+                <= utils::calculate_allocation(
+                    &(self.total_supply as u128),
+                    &(max_holding_bps as u128)
+                ),
+            errors::CustomErrors::MaxHoldingExceeded
+        );
+
+        Ok(())
+    }
+
+    /// Checks the curve is still open for trading
+    pub fn check_tradable(&self) -> Result<()> {
+        require!(!self.launched, errors::CustomErrors::AlreadyLaunched);
+        require!(!self.halted, errors::CustomErrors::TokenHalted);
+
+        Ok(())
+    }
+
+    /// Returns the curve price in sol per token, as a Q64.64 fixed point number
+    pub fn price(&self) -> u128 {
+        ((self.sol_reserve as u128) << 64) / self.token_reserve as u128
+    }
+
+    /// Returns the cumulative price extrapolated to `now`, consumers derive the twap between
+    /// two points in time as the difference of their cumulative prices over the elapsed seconds
+    pub fn current_price_cumulative(&self, now: i64) -> u128 {
+        let elapsed = now.saturating_sub(self.last_price_update).max(0) as u128;
+
+        self.price_cumulative
+            .wrapping_add(self.price().wrapping_mul(elapsed))
+    }
+
+    /// Accrues the price held since the last trade, must run before the reserves change
+    fn accumulate_price(&mut self, now: i64) {
+        self.price_cumulative = self.current_price_cumulative(now);
+        self.last_price_update = now;
+    }
+
+    /// Moves `sol_amount` (after fees) into the curve and returns the tokens bought,
+    /// launching the token once the target pool balance is hit
+    pub fn buy(&mut self, sol_amount: u64, now: i64) -> Result<u64> {
+        self.accumulate_price(now);
+
+        let token_amount = utils::get_amount_out(
+            &(sol_amount as u128),
+            &(self.sol_reserve as u128),
+            &(self.token_reserve as u128),
+        );
+
+        self.sol_reserve += sol_amount;
+        self.token_reserve -= token_amount;
+
+        require!(
+            self.sol_reserve <= self.target_pool_balance,
+            errors::CustomErrors::BondingCurveBreached
+        );
+
+        // Check the current market cap and launch the token if it's been hit
+        if self.sol_reserve >= self.target_pool_balance {
+            self.launched = true;
+        }
+
+        Ok(token_amount)
+    }
+
+    /// Moves `token_amount` into the curve and returns the sol (before fees) it's sold for
+    pub fn sell(&mut self, token_amount: u64, now: i64) -> u64 {
+        self.accumulate_price(now);
+
+        let sol_amount = utils::get_amount_out(
+            &(token_amount as u128),
+            &(self.token_reserve as u128),
+            &(self.sol_reserve as u128),
+        );
+
+        self.sol_reserve -= sol_amount;
+        self.token_reserve += token_amount;
+
+        sol_amount
+    }
+}
+
+/// Bumps of the token PDAs, stored at creation so trades don't re-derive them
+pub struct TokenInfoBumps {
+    pub token_info: u8,
+    pub token_account: u8,
+    pub quote_vault: u8,
+}
+
+/// Ring buffer of the cumulative prices of a token, one observation per second at most
+#[account]
+#[derive(InitSpace)]
+pub struct PriceObservations {
+    pub token: Pubkey,
+    pub bump: u8,
+    /// Slot of the latest observation
+    pub index: u16,
+    pub observations: [PriceObservation; MAX_PRICE_OBSERVATIONS],
+}
+
+impl PriceObservations {
+    pub fn initialize(&mut self, token_info: &TokenInfo, bump: u8) {
+        self.token = token_info.token;
+        self.bump = bump;
+        self.index = 0;
+        self.observations[0] = PriceObservation {
+            timestamp: token_info.last_price_update,
+            price_cumulative: token_info.price_cumulative,
+        };
+    }
+
+    /// Records the cumulative price of `token_info` as of its last trade
+    pub fn record(&mut self, token_info: &TokenInfo) {
+        let latest = &self.observations[self.index as usize];
+        if latest.timestamp == token_info.last_price_update {
+            return;
+        }
+
+        self.index = (self.index + 1) % MAX_PRICE_OBSERVATIONS as u16;
+        self.observations[self.index as usize] = PriceObservation {
+            timestamp: token_info.last_price_update,
+            price_cumulative: token_info.price_cumulative,
+        };
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Debug, Clone, Copy, Default)]
+pub struct PriceObservation {
+    pub timestamp: i64,
+    pub price_cumulative: u128,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TokenProfile {
+    pub token: Pubkey,
+    #[max_len(MAX_DESCRIPTION_LEN)]
+    pub description: String,
+    #[max_len(MAX_LINK_LEN)]
+    pub website: String,
+    #[max_len(MAX_LINK_LEN)]
+    pub twitter: String,
+    #[max_len(MAX_LINK_LEN)]
+    pub telegram: String,
+    /// Sha256 of the off-chain JSON referenced by the metadata uri
+    pub content_hash: [u8; 32],
+}
+
+impl TokenProfile {
+    pub fn apply(&mut self, token_profile_params: &TokenProfileParams) -> Result<()> {
+        require!(
+            token_profile_params.description.len() <= MAX_DESCRIPTION_LEN
+                && token_profile_params.website.len() <= MAX_LINK_LEN
+                && token_profile_params.twitter.len() <= MAX_LINK_LEN
+                && token_profile_params.telegram.len() <= MAX_LINK_LEN,
+            errors::CustomErrors::TokenProfileFieldTooLong
+        );
+
+        self.description = token_profile_params.description.clone();
+        self.website = token_profile_params.website.clone();
+        self.twitter = token_profile_params.twitter.clone();
+        self.telegram = token_profile_params.telegram.clone();
+        self.content_hash = token_profile_params.content_hash;
+
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AllowedCreator {
+    pub platform: Pubkey,
+    pub creator: Pubkey,
+}
+
+/// Reserved token name or symbol, blocked for everyone but `creator` if assigned
+#[account]
+#[derive(InitSpace)]
+pub struct Reservation {
+    pub platform: Pubkey,
+    pub hash: [u8; 32],
+    pub creator: Option<Pubkey>,
+}
+
+impl Reservation {
+    /// Fails if the name behind `reservation` is reserved, unless it is assigned to `creator`
+    pub fn check_available(reservation: &AccountInfo, creator: &Pubkey) -> Result<()> {
+        if reservation.data_is_empty() {
+            return Ok(());
+        }
+
+        require_keys_eq!(
+            *reservation.owner,
+            crate::ID,
+            ErrorCode::AccountOwnedByWrongProgram
+        );
+        let reservation = Reservation::try_deserialize(&mut &reservation.try_borrow_data()?[..])?;
+        require!(
+            reservation.creator == Some(*creator),
+            errors::CustomErrors::NameReserved
+        );
+
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PendingChange {
+    pub platform: Pubkey,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub operation: PendingOperation,
+    pub eta: i64,
+}
+
+/// Owner operation held back by the timelock until its pending change executes
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Debug, Clone)]
+pub enum PendingOperation {
+    UpdateConfig {
+        params: UpdateConfigParams,
+    },
+    AddReservation {
+        #[max_len(MAX_RESERVATION_LEN)]
+        value: String,
+        creator: Option<Pubkey>,
+    },
+    RemoveReservation {
+        hash: [u8; 32],
+    },
+    ResumeToken {
+        token: Pubkey,
+    },
+}
+
+impl PendingOperation {
+    /// Hash of the reservation this operation adds, zeroed for every other operation
+    pub fn reservation_hash(&self) -> [u8; 32] {
+        match self {
+            PendingOperation::AddReservation { value, .. } => utils::get_reservation_hash(value),
+            _ => [0; 32],
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Referral {
+    pub referrer: Pubkey,
+    pub unclaimed_fees: u64,
+    pub total_fees_earned: u64,
+    pub referral_volume: u64,
+    pub trade_count: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vesting {
+    pub token: Pubkey,
+    pub beneficiary: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub end_time: i64,
+}
+
+impl Vesting {
+    pub fn initialize(
+        &mut self,
+        token: Pubkey,
+        beneficiary: Pubkey,
+        total_amount: u64,
+        create_token_params: &CreateTokenParams,
+        now: i64,
+    ) -> Result<()> {
+        require!(
+            create_token_params.vesting_duration > 0
+                && create_token_params.vesting_cliff >= 0
+                && create_token_params.vesting_cliff <= create_token_params.vesting_duration,
+            errors::CustomErrors::InvalidVestingSchedule
+        );
+
+        self.token = token;
+        self.beneficiary = beneficiary;
+        self.total_amount = total_amount;
+        self.claimed_amount = 0;
+        self.start_time = now;
+        self.cliff_time = now
+            .checked_add(create_token_params.vesting_cliff)
+            .ok_or(errors::CustomErrors::InvalidVestingSchedule)?;
+        self.end_time = now
+            .checked_add(create_token_params.vesting_duration)
+            .ok_or(errors::CustomErrors::InvalidVestingSchedule)?;
+
+        Ok(())
+    }
 }
 
 // Params
 
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct BatchBuyParams {
+    pub sol_amount: u64,
+    pub min_token_amount_out: u64,
+}
+
+/// Outcome of a trade at the current curve state, `amount_out` is net of all fees
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct TradeQuote {
+    pub fee_in_bps: u64,
+    pub fee_amount: u64,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
 pub struct PlatformInitParams {
+    pub id: u64,
     pub owner: Pubkey,
+    pub treasury: Pubkey,
+    pub fee_in_bps: u64,
+    pub referral_fee_in_bps: u64,
+    pub total_supply: u64,
+    pub virtual_sol: u64,
+    pub target_pool_balance: u64,
+    pub timelock_delay: i64,
+    pub launch_mode: LaunchMode,
+    pub creation_fee: u64,
+    pub creation_fee_waived_for_allowlisted: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Debug, Clone)]
+pub struct UpdateConfigParams {
+    pub fee_in_bps: Option<u64>,
+    pub fee_schedule: Option<FeeSchedule>,
+    pub referral_fee_in_bps: Option<u64>,
+    pub total_supply: Option<u64>,
+    pub virtual_sol: Option<u64>,
+    pub target_pool_balance: Option<u64>,
+    pub timelock_delay: Option<i64>,
+    pub creation_fee: Option<u64>,
+    pub creation_fee_waived_for_allowlisted: Option<bool>,
+    pub owner: Option<Pubkey>,
+    pub treasury: Option<Pubkey>,
+    pub launch_mode: Option<LaunchMode>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct PlatformConfig {
     pub fee_in_bps: u64,
+    pub fee_schedule: FeeSchedule,
+    pub referral_fee_in_bps: u64,
     pub total_supply: u64,
     pub virtual_sol: u64,
     pub target_pool_balance: u64,
+    pub timelock_delay: i64,
+    pub creation_fee: u64,
+    pub creation_fee_waived_for_allowlisted: bool,
+    pub owner: Pubkey,
+    pub treasury: Pubkey,
+    pub launch_mode: LaunchMode,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct CreateCollectionParams {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
@@ -577,4 +3518,53 @@ pub struct CreateTokenParams {
     pub name: String,
     pub symbol: String,
     pub uri: String,
+    pub creator_allocation_bps: u64,
+    pub vesting_cliff: i64,
+    pub vesting_duration: i64,
+    pub metadata_authority: MetadataAuthority,
+    pub profile: Option<TokenProfileParams>,
+    /// Share of the total supply a single wallet may hold from curve buys, uncapped if none
+    pub max_holding_bps: Option<u64>,
+}
+
+impl CreateTokenParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.creator_allocation_bps <= constants::general::MAX_CREATOR_ALLOCATION_IN_BPS,
+            errors::CustomErrors::ExcessiveCreatorAllocation
+        );
+        require!(
+            !matches!(self.max_holding_bps, Some(bps) if bps == 0 || bps > constants::general::BPS as u64),
+            errors::CustomErrors::InvalidMaxHolding
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct TokenProfileParams {
+    pub description: String,
+    pub website: String,
+    pub twitter: String,
+    pub telegram: String,
+    pub content_hash: [u8; 32],
+}
+
+/// Token-2022 transfer fee withheld on every transfer of the token, withdrawable by the creator
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct TransferFeeParams {
+    pub transfer_fee_bps: u16,
+    pub maximum_transfer_fee: u64,
+}
+
+impl TransferFeeParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.transfer_fee_bps <= constants::general::MAX_TRANSFER_FEE_IN_BPS,
+            errors::CustomErrors::ExcessiveTransferFee
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{general, seeds};
+use crate::{FeeSchedule, LaunchMode, Platform, TokenInfo};
+
+/// `Platform` layout before accounts carried a version
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PlatformV0 {
+    pub owner: Pubkey,
+    pub fee_in_bps: u64,
+    pub accumulated_fees: u64,
+    pub total_supply: u64,
+    pub virtual_sol: u64,
+    pub target_pool_balance: u64,
+}
+
+impl PlatformV0 {
+    pub const SIZE: usize = general::DISCRIMINATOR_SIZE + 32 + 8 * 5;
+}
+
+impl From<PlatformV0> for Platform {
+    fn from(legacy: PlatformV0) -> Self {
+        Platform {
+            version: general::PLATFORM_VERSION,
+            id: general::LEGACY_PLATFORM_ID,
+            owner: legacy.owner,
+            treasury: legacy.owner,
+            fee_in_bps: legacy.fee_in_bps,
+            fee_schedule: FeeSchedule::default(),
+            referral_fee_in_bps: 0,
+            accumulated_fees: legacy.accumulated_fees,
+            total_supply: legacy.total_supply,
+            virtual_sol: legacy.virtual_sol,
+            target_pool_balance: legacy.target_pool_balance,
+            config_version: 0,
+            timelock_delay: 0,
+            pending_change_nonce: 0,
+            launch_mode: LaunchMode::Open,
+            creation_fee: 0,
+            creation_fee_waived_for_allowlisted: false,
+            quote_mint: Pubkey::default(),
+            accumulated_quote_fees: 0,
+            collection_mint: Pubkey::default(),
+            // Set once the legacy platform is migrated
+            bump: 0,
+            fee_vault_bump: 0,
+        }
+    }
+}
+
+/// `TokenInfo` layout before accounts carried a version
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct TokenInfoV0 {
+    pub token: Pubkey,
+    pub creator: Pubkey,
+    pub total_supply: u64,
+    pub virtual_sol: u64,
+    pub sol_reserve: u64,
+    pub token_reserve: u64,
+    pub target_pool_balance: u64,
+    pub launched: bool,
+}
+
+impl TokenInfoV0 {
+    pub const SIZE: usize = general::DISCRIMINATOR_SIZE + 32 * 2 + 8 * 5 + 1;
+}
+
+impl From<TokenInfoV0> for TokenInfo {
+    fn from(legacy: TokenInfoV0) -> Self {
+        TokenInfo {
+            version: general::TOKEN_INFO_VERSION,
+            // Legacy tokens were all launched on the singleton platform
+            platform: Pubkey::find_program_address(&[seeds::PLATFORM_SEED], &crate::ID).0,
+            token: legacy.token,
+            creator: legacy.creator,
+            total_supply: legacy.total_supply,
+            creator_allocation: 0,
+            virtual_sol: legacy.virtual_sol,
+            sol_reserve: legacy.sol_reserve,
+            token_reserve: legacy.token_reserve,
+            target_pool_balance: legacy.target_pool_balance,
+            config_version: 0,
+            // The launch time of legacy tokens is unknown, treat them as launched long ago
+            created_at: 0,
+            launched: legacy.launched,
+            halted: false,
+            quote_mint: Pubkey::default(),
+            // Set once the token info is relocated to its mint derived address
+            bump: 0,
+            token_account_bump: 0,
+            quote_vault_bump: 0,
+            price_cumulative: 0,
+            last_price_update: 0,
+            max_holding_bps: None,
+        }
+    }
+}
+
+/// Returns the layout version of a raw account, legacy accounts are recognised by their size.
+/// Versioned layouts are told apart by their version byte: a layout change keeps the previous
+/// layout here as a `*V{n}` struct with a `From` impl and matches it in the migrate instruction
+pub fn read_version(data: &[u8], legacy_size: usize) -> u8 {
+    if data.len() == legacy_size {
+        0
+    } else {
+        data[general::DISCRIMINATOR_SIZE]
+    }
+}
+
+/// Grows `account` to `new_len`, topping it up to the new rent exempt minimum from `payer`.
+/// `reserved_lamports` are held by the account on top of its rent (e.g. accumulated fees)
+/// and are never counted towards the new minimum
+pub fn realloc_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+    reserved_lamports: u64,
+) -> Result<()> {
+    let minimum_balance = Rent::get()?.minimum_balance(new_len);
+    let top_up = (minimum_balance + reserved_lamports).saturating_sub(account.lamports());
+
+    if top_up > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+
+    account.realloc(new_len, true)?;
+
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
+        StateWithExtensions,
+    },
+    state::{Account as SplAccount, Mint as SplMint},
+};
+use anchor_spl::token_interface::{
+    initialize_account3, initialize_mint2, metadata_pointer_initialize,
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_metadata_interface::state::TokenMetadata, token_metadata_initialize,
+    token_metadata_update_authority, transfer_fee_initialize, InitializeAccount3, InitializeMint2,
+    MetadataPointerInitialize, TokenMetadataInitialize, TokenMetadataUpdateAuthority,
+    TransferFeeInitialize,
+};
+
+use crate::constants::general;
+use crate::{CreateTokenParams, TransferFeeParams};
+
+/// Creates a Token-2022 mint at the `mint` PDA carrying its own token metadata,
+/// with the mint as mint authority and `metadata_update_authority` as metadata update authority
+/// (immutable metadata if none)
+#[allow(clippy::too_many_arguments)]
+pub fn create_mint<'info>(
+    payer: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    mint_signer: &[&[&[u8]]],
+    create_token_params: &CreateTokenParams,
+    transfer_fee_params: Option<&TransferFeeParams>,
+    creator: &Pubkey,
+    metadata_update_authority: Option<Pubkey>,
+) -> Result<()> {
+    let mut extensions = vec![ExtensionType::MetadataPointer];
+    if transfer_fee_params.is_some() {
+        extensions.push(ExtensionType::TransferFeeConfig);
+    }
+    let space = ExtensionType::try_calculate_account_len::<SplMint>(&extensions)?;
+
+    // The token program reallocs the mint to append the metadata, so fund it upfront
+    let token_metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey::try_from(Some(mint.key()))?,
+        mint: mint.key(),
+        name: create_token_params.name.clone(),
+        symbol: create_token_params.symbol.clone(),
+        uri: create_token_params.uri.clone(),
+        additional_metadata: vec![],
+    };
+    let lamports = Rent::get()?.minimum_balance(space + token_metadata.tlv_size_of()?);
+
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::CreateAccount {
+                from: payer.clone(),
+                to: mint.clone(),
+            },
+            mint_signer,
+        ),
+        lamports,
+        space as u64,
+        token_program.key,
+    )?;
+
+    metadata_pointer_initialize(
+        CpiContext::new(
+            token_program.clone(),
+            MetadataPointerInitialize {
+                token_program_id: token_program.clone(),
+                mint: mint.clone(),
+            },
+        ),
+        Some(mint.key()),
+        Some(mint.key()),
+    )?;
+
+    if let Some(transfer_fee_params) = transfer_fee_params {
+        // The fee rate is fixed, and only the creator can withdraw the withheld fees
+        transfer_fee_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                TransferFeeInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            None,
+            Some(creator),
+            transfer_fee_params.transfer_fee_bps,
+            transfer_fee_params.maximum_transfer_fee,
+        )?;
+    }
+
+    initialize_mint2(
+        CpiContext::new(
+            token_program.clone(),
+            InitializeMint2 { mint: mint.clone() },
+        ),
+        general::DECIMALS,
+        &mint.key(),
+        None,
+    )?;
+
+    token_metadata_initialize(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            TokenMetadataInitialize {
+                token_program_id: token_program.clone(),
+                metadata: mint.clone(),
+                update_authority: mint.clone(),
+                mint_authority: mint.clone(),
+                mint: mint.clone(),
+            },
+            mint_signer,
+        ),
+        token_metadata.name,
+        token_metadata.symbol,
+        token_metadata.uri,
+    )?;
+
+    // The metadata is initialized with the mint as update authority, hand it over afterwards
+    token_metadata_update_authority(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            TokenMetadataUpdateAuthority {
+                token_program_id: token_program.clone(),
+                metadata: mint.clone(),
+                current_authority: mint.clone(),
+                new_authority: mint.clone(),
+            },
+            mint_signer,
+        ),
+        OptionalNonZeroPubkey::try_from(metadata_update_authority)?,
+    )?;
+
+    Ok(())
+}
+
+/// Creates a Token-2022 token account at the `account` PDA, owned by itself and sized
+/// for the account extensions required by the mint
+pub fn create_token_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    account_signer: &[&[&[u8]]],
+) -> Result<()> {
+    let account_extensions = {
+        let mint_data = mint.try_borrow_data()?;
+        let mint_state = StateWithExtensions::<SplMint>::unpack(&mint_data)?;
+        ExtensionType::get_required_init_account_extensions(&mint_state.get_extension_types()?)
+    };
+    let space = ExtensionType::try_calculate_account_len::<SplAccount>(&account_extensions)?;
+
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::CreateAccount {
+                from: payer.clone(),
+                to: account.clone(),
+            },
+            account_signer,
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        token_program.key,
+    )?;
+
+    initialize_account3(CpiContext::new(
+        token_program.clone(),
+        InitializeAccount3 {
+            account: account.clone(),
+            mint: mint.clone(),
+            authority: account.clone(),
+        },
+    ))?;
+
+    Ok(())
+}
+
+/// Returns the fee withheld when transferring `amount` of `mint`,
+/// zero for mints without the transfer fee extension
+pub fn get_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != anchor_spl::token_2022::ID {
+        return Ok(0);
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<SplMint>::unpack(&mint_data)?;
+    let Ok(transfer_fee_config) = mint_state.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+
+    Ok(transfer_fee_config
+        .calculate_epoch_fee(Clock::get()?.epoch, amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?)
+}
@@ -1,4 +1,8 @@
-use crate::constants::general;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hash;
+
+use crate::constants::{general, seeds};
+use crate::{FeeSchedule, FeeScheduleKind};
 
 pub fn get_amount_out(amount_in: &u128, reserve_in: &u128, reserve_out: &u128) -> u64 {
     (amount_in * reserve_out / (amount_in + reserve_in)) as u64
@@ -15,3 +19,86 @@ pub fn calculate_sell_fee(amount: &u128, fee_in_bps: &u128) -> u64 {
 pub fn calculate_buy_fee(amount: &u128, fee_in_bps: &u128) -> u64 {
     (amount * fee_in_bps / (general::BPS as u128 + fee_in_bps)) as u64
 }
+
+pub fn get_fee_in_bps(
+    fee_schedule: &FeeSchedule,
+    base_fee_in_bps: u64,
+    sol_reserve: u64,
+    target_pool_balance: u64,
+    seconds_since_launch: i64,
+) -> u64 {
+    // Curve progress is expressed in bips of the target pool balance
+    let position = match fee_schedule.kind {
+        FeeScheduleKind::Flat => return base_fee_in_bps,
+        FeeScheduleKind::CurveProgress => {
+            (sol_reserve as u128 * general::BPS as u128 / target_pool_balance as u128) as u64
+        }
+        FeeScheduleKind::TimeSinceLaunch => seconds_since_launch.max(0) as u64,
+    };
+
+    fee_schedule
+        .tiers
+        .iter()
+        .take_while(|tier| tier.threshold <= position)
+        .last()
+        .map_or(base_fee_in_bps, |tier| tier.fee_in_bps)
+}
+
+pub fn calculate_referral_fee(fee_amount: &u128, referral_fee_in_bps: &u128) -> u64 {
+    ((fee_amount * referral_fee_in_bps) / general::BPS as u128) as u64
+}
+
+pub fn calculate_allocation(amount: &u128, allocation_in_bps: &u128) -> u64 {
+    ((amount * allocation_in_bps) / general::BPS as u128) as u64
+}
+
+pub fn calculate_vested_amount(
+    total_amount: u64,
+    start_time: i64,
+    cliff_time: i64,
+    end_time: i64,
+    now: i64,
+) -> u64 {
+    if now < cliff_time {
+        return 0;
+    }
+    if now >= end_time {
+        return total_amount;
+    }
+
+    ((total_amount as u128 * (now - start_time) as u128) / (end_time - start_time) as u128) as u64
+}
+
+/// Hashes a token name or symbol, ignoring case, whitespace and punctuation
+/// so lookalikes of a reserved ticker map to the same reservation
+pub fn get_reservation_hash(value: &str) -> [u8; 32] {
+    let normalized: String = value
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    hash(normalized.as_bytes()).to_bytes()
+}
+
+/// Returns whether `key` is the address of this program derived from `seeds` and `bump`
+pub fn is_program_address(key: &Pubkey, seeds: &[&[u8]], bump: u8) -> bool {
+    let bump = [bump];
+    let seeds = [seeds, &[&bump[..]]].concat();
+
+    Pubkey::create_program_address(&seeds, &crate::ID).ok() == Some(*key)
+}
+
+/// Returns the platform seed and bump the mint of the token `name` on `platform` was derived from,
+/// if `mint` is that mint. Mints launched before they were scoped by platform omit the platform seed
+pub fn find_mint_seeds(mint: &Pubkey, platform: &Pubkey, name: &str) -> Option<(Vec<u8>, u8)> {
+    [platform.as_ref(), &[]]
+        .into_iter()
+        .find_map(|platform_seed| {
+            let (address, bump) = Pubkey::find_program_address(
+                &[seeds::MINT_SEED, platform_seed, name.as_bytes()],
+                &crate::ID,
+            );
+            (address == *mint).then(|| (platform_seed.to_vec(), bump))
+        })
+}
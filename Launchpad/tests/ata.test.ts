@@ -0,0 +1,79 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  TOKEN_PROGRAM_ID,
+  associatedAddress,
+  createToken,
+  expectFailure,
+  fundedKeypair,
+  initializePlatform,
+  tokenName,
+  tradeAccounts,
+} from "./fixtures";
+
+describe("Associated token account creation on buy", () => {
+  let platform;
+  let token;
+  let buyer;
+
+  const buy = (userTokenAccount) =>
+    pg.program.methods
+      .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 100))
+      .accounts(tradeAccounts(platform, token, { signer: buyer.publicKey, userTokenAccount }))
+      .signers([buyer])
+      .rpc();
+
+  before(async () => {
+    platform = await initializePlatform();
+    token = await createToken(platform, tokenName("ata", platform));
+    buyer = await fundedKeypair(0.1);
+  });
+
+  it("opens the buyer's token account on their first buy", async () => {
+    const userTokenAccount = associatedAddress(token.mint, buyer.publicKey);
+    assert.equal(await pg.connection.getAccountInfo(userTokenAccount), null);
+
+    await buy(userTokenAccount);
+
+    const balance = await pg.connection.getTokenAccountBalance(userTokenAccount);
+    assert(new anchor.BN(balance.value.amount).gtn(0));
+
+    // Later buys reuse the same account
+    await buy(userTokenAccount);
+  });
+
+  it("opens a token account ahead of the first buy, tolerating an existing one", async () => {
+    const owner = await fundedKeypair(0.1);
+    const tokenAccount = associatedAddress(token.mint, owner.publicKey);
+    const initTokenAccount = () =>
+      pg.program.methods
+        .initTokenAccountForToken()
+        .accounts({
+          signer: owner.publicKey,
+          mint: token.mint,
+          tokenAccount,
+          systemProgram: web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([owner])
+        .rpc();
+
+    await initTokenAccount();
+    assert.notEqual(await pg.connection.getAccountInfo(tokenAccount), null);
+
+    await initTokenAccount();
+  });
+
+  it("rejects a token account owned by someone else", async () => {
+    await pg.program.methods
+      .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 100))
+      .accounts(tradeAccounts(platform, token))
+      .rpc();
+
+    await expectFailure(
+      buy(associatedAddress(token.mint, pg.wallet.publicKey)),
+      "ConstraintTokenOwner"
+    );
+  });
+});
@@ -0,0 +1,87 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  createToken,
+  expectFailure,
+  initializePlatform,
+  tokenName,
+  tradeAccounts,
+} from "./fixtures";
+
+describe("Treasury reconciliation", () => {
+  let platform;
+  let token;
+
+  const auditEvent = async (method, accounts, name) => {
+    const simulation = await method.accounts(accounts).simulate();
+    return simulation.events.find((e) => e.name === name).data;
+  };
+
+  before(async () => {
+    platform = await initializePlatform();
+    token = await createToken(platform, tokenName("audit", platform));
+
+    await pg.program.methods
+      .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 10))
+      .accounts(tradeAccounts(platform, token))
+      .rpc();
+  });
+
+  it("balances the platform against its accumulated fees", async () => {
+    const audited = await auditEvent(
+      pg.program.methods.auditPlatform(),
+      { platform: platform.address, feeVault: null },
+      "platformAudited"
+    );
+
+    const { accumulatedFees } = await pg.program.account.platform.fetch(platform.address);
+    assert(accumulatedFees.gtn(0));
+    assert(audited.shortfall.isZero());
+    assert(audited.surplus.isZero());
+  });
+
+  it("reports a surplus on the token without halting it", async () => {
+    const surplus = 1_000_000;
+    await pg.program.provider.sendAndConfirm(
+      new web3.Transaction().add(
+        web3.SystemProgram.transfer({
+          fromPubkey: pg.wallet.publicKey,
+          toPubkey: token.tokenInfo,
+          lamports: surplus,
+        })
+      )
+    );
+
+    await pg.program.methods
+      .auditToken(true)
+      .accounts({ tokenInfo: token.tokenInfo, quoteVault: null })
+      .rpc();
+
+    const audited = await auditEvent(
+      pg.program.methods.auditToken(true),
+      { tokenInfo: token.tokenInfo, quoteVault: null },
+      "tokenAudited"
+    );
+    assert(audited.shortfall.isZero());
+    assert(audited.surplus.eqn(surplus));
+    assert(!audited.halted);
+    assert(!(await pg.program.account.tokenInfo.fetch(token.tokenInfo)).halted);
+  });
+
+  it("rejects resuming a token from anyone but the owner", async () => {
+    const stranger = web3.Keypair.generate();
+
+    await expectFailure(
+      pg.program.methods
+        .resumeToken()
+        .accounts({
+          platform: platform.address,
+          signer: stranger.publicKey,
+          tokenInfo: token.tokenInfo,
+        })
+        .signers([stranger])
+        .rpc(),
+      "NotOwner"
+    );
+  });
+});
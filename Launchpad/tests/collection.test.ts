@@ -0,0 +1,141 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  TOKEN_METADATA_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createToken,
+  expectFailure,
+  findPda,
+  initializePlatform,
+  tokenName,
+} from "./fixtures";
+
+/**
+ * Derives a Metaplex account of `mint`
+ */
+const metaplexPda = (mint, suffix = []) =>
+  web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), ...suffix],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+
+/**
+ * Reads the creators and collection of a Metaplex metadata account
+ */
+const readMetadata = async (metadata) => {
+  const data = (await pg.connection.getAccountInfo(metadata)).data;
+  // Key, update authority and mint
+  let offset = 1 + 32 + 32;
+  // Name, symbol and uri
+  for (let i = 0; i < 3; i++) {
+    offset += 4 + data.readUInt32LE(offset);
+  }
+  // Seller fee basis points
+  offset += 2;
+
+  const creators = [];
+  if (data[offset++] === 1) {
+    const count = data.readUInt32LE(offset);
+    offset += 4;
+    for (let i = 0; i < count; i++, offset += 34) {
+      creators.push({
+        address: new web3.PublicKey(data.subarray(offset, offset + 32)),
+        verified: data[offset + 32] === 1,
+      });
+    }
+  }
+  // Primary sale happened and is mutable
+  offset += 2;
+  // Edition nonce and token standard
+  for (let i = 0; i < 2; i++) {
+    offset += data[offset] === 1 ? 2 : 1;
+  }
+
+  const collection =
+    data[offset] === 1
+      ? {
+          verified: data[offset + 1] === 1,
+          key: new web3.PublicKey(data.subarray(offset + 2, offset + 34)),
+        }
+      : null;
+
+  return { creators, collection };
+};
+
+describe("Platform collection", () => {
+  let platform;
+  let collectionMint;
+
+  const createCollection = (signer = null) =>
+    pg.program.methods
+      .createPlatformCollection({
+        name: "Launchpad",
+        symbol: "LPAD",
+        uri: "https://example.com/collection.json",
+      })
+      .accounts({
+        platform: platform.address,
+        signer: signer?.publicKey ?? pg.wallet.publicKey,
+        collectionMint,
+        collectionTokenAccount: findPda([
+          Buffer.from("collection_account"),
+          platform.address.toBuffer(),
+        ]),
+        collectionMetadata: metaplexPda(collectionMint),
+        collectionMasterEdition: metaplexPda(collectionMint, [Buffer.from("edition")]),
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        rent: web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers(signer ? [signer] : [])
+      .preInstructions([
+        web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+      ])
+      .rpc();
+
+  before(async () => {
+    platform = await initializePlatform();
+    collectionMint = findPda([Buffer.from("collection"), platform.address.toBuffer()]);
+  });
+
+  it("rejects creating the collection from anyone but the owner", async () => {
+    await expectFailure(createCollection(web3.Keypair.generate()), "NotOwner");
+  });
+
+  it("creates the collection once", async () => {
+    await createCollection();
+
+    const platformAccount = await pg.program.account.platform.fetch(platform.address);
+    assert(platformAccount.collectionMint.equals(collectionMint));
+
+    await expectFailure(createCollection(), "CollectionAlreadyCreated");
+  });
+
+  it("rejects launching without the collection accounts", async () => {
+    await expectFailure(
+      createToken(platform, tokenName("uncollected", platform)),
+      "CollectionAccountsMissing"
+    );
+  });
+
+  it("verifies the creator and the collection of launched tokens", async () => {
+    const token = await createToken(
+      platform,
+      tokenName("collected", platform),
+      {},
+      {
+        collectionMint,
+        collectionMetadata: metaplexPda(collectionMint),
+        collectionMasterEdition: metaplexPda(collectionMint, [Buffer.from("edition")]),
+      }
+    );
+
+    const { creators, collection } = await readMetadata(token.metadata);
+    assert.equal(creators.length, 1);
+    assert(creators[0].address.equals(pg.wallet.publicKey));
+    assert(creators[0].verified);
+    assert(collection.key.equals(collectionMint));
+    assert(collection.verified);
+  });
+});
@@ -0,0 +1,52 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  computeUnits,
+  createToken,
+  initializePlatform,
+  tokenName,
+  tradeAccounts,
+} from "./fixtures";
+
+// Ceilings of the trade instructions, raise them deliberately when a change needs more
+const MAX_COMPUTE_UNITS = {
+  buyTokens: 80_000,
+  sellTokens: 80_000,
+};
+
+describe("Trade compute units", () => {
+  let platform;
+  let token;
+
+  before(async () => {
+    platform = await initializePlatform();
+
+    token = await createToken(platform, tokenName("bench", platform));
+
+    // The first buy creates the user token account, keep it out of the measurements
+    await pg.program.methods
+      .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 10))
+      .accounts(tradeAccounts(platform, token))
+      .rpc();
+  });
+
+  it("keeps buy_tokens under its ceiling", async () => {
+    const signature = await pg.program.methods
+      .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 10))
+      .accounts(tradeAccounts(platform, token))
+      .rpc();
+
+    const units = await computeUnits(signature);
+    assert(units <= MAX_COMPUTE_UNITS.buyTokens, `buy_tokens used ${units} units`);
+  });
+
+  it("keeps sell_tokens under its ceiling", async () => {
+    const signature = await pg.program.methods
+      .sellTokens(new anchor.BN(1_000_000))
+      .accounts(tradeAccounts(platform, token))
+      .rpc();
+
+    const units = await computeUnits(signature);
+    assert(units <= MAX_COMPUTE_UNITS.sellTokens, `sell_tokens used ${units} units`);
+  });
+});
@@ -0,0 +1,111 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import { expectFailure, initializePlatform } from "./fixtures";
+
+describe("Platform config validation", () => {
+  let platform;
+
+  const configAccounts = () => ({
+    platform: platform.address,
+    signer: pg.wallet.publicKey,
+    systemProgram: web3.SystemProgram.programId,
+  });
+
+  before(async () => {
+    platform = await initializePlatform();
+  });
+
+  it("rejects initializing a platform with an invalid config", async () => {
+    await expectFailure(
+      initializePlatform({
+        virtualSol: new anchor.BN(85 * web3.LAMPORTS_PER_SOL),
+        targetPoolBalance: new anchor.BN(30 * web3.LAMPORTS_PER_SOL),
+      }),
+      "TargetPoolBalanceBelowVirtualSol"
+    );
+  });
+
+  it("applies setters within the bounds", async () => {
+    await pg.program.methods.changeFees(new anchor.BN(500)).accounts(configAccounts()).rpc();
+    await pg.program.methods
+      .changeReferralFees(new anchor.BN(10_000))
+      .accounts(configAccounts())
+      .rpc();
+    await pg.program.methods
+      .changeTargetPoolBalance(new anchor.BN(31 * web3.LAMPORTS_PER_SOL))
+      .accounts(configAccounts())
+      .rpc();
+
+    const platformAccount = await pg.program.account.platform.fetch(platform.address);
+    assert(platformAccount.feeInBps.eqn(500));
+    assert(platformAccount.referralFeeInBps.eqn(10_000));
+    assert(platformAccount.targetPoolBalance.eqn(31 * web3.LAMPORTS_PER_SOL));
+  });
+
+  it("rejects fees above the maximum", async () => {
+    await expectFailure(
+      pg.program.methods.changeFees(new anchor.BN(501)).accounts(configAccounts()).rpc(),
+      "ExcessiveFees"
+    );
+    await expectFailure(
+      pg.program.methods
+        .changeReferralFees(new anchor.BN(10_001))
+        .accounts(configAccounts())
+        .rpc(),
+      "ExcessiveReferralFees"
+    );
+  });
+
+  it("rejects an empty curve", async () => {
+    await expectFailure(
+      pg.program.methods.changeTotalSupply(new anchor.BN(0)).accounts(configAccounts()).rpc(),
+      "ZeroTotalSupply"
+    );
+    await expectFailure(
+      pg.program.methods
+        .changeVirtualSolAmount(new anchor.BN(0))
+        .accounts(configAccounts())
+        .rpc(),
+      "ZeroVirtualSol"
+    );
+  });
+
+  it("rejects a target pool balance below the virtual sol", async () => {
+    await expectFailure(
+      pg.program.methods
+        .changeTargetPoolBalance(new anchor.BN(30 * web3.LAMPORTS_PER_SOL))
+        .accounts(configAccounts())
+        .rpc(),
+      "TargetPoolBalanceBelowVirtualSol"
+    );
+  });
+
+  it("rejects fee tiers out of order", async () => {
+    await expectFailure(
+      pg.program.methods
+        .changeFeeSchedule({
+          kind: { timeSinceLaunch: {} },
+          tiers: [
+            { threshold: new anchor.BN(60), feeInBps: new anchor.BN(100) },
+            { threshold: new anchor.BN(30), feeInBps: new anchor.BN(200) },
+          ],
+        })
+        .accounts(configAccounts())
+        .rpc(),
+      "InvalidFeeSchedule"
+    );
+  });
+
+  it("rejects setters from anyone but the owner", async () => {
+    const stranger = web3.Keypair.generate();
+
+    await expectFailure(
+      pg.program.methods
+        .changeFees(new anchor.BN(100))
+        .accounts({ ...configAccounts(), signer: stranger.publicKey })
+        .signers([stranger])
+        .rpc(),
+      "NotOwner"
+    );
+  });
+});
@@ -0,0 +1,136 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  configParams,
+  createToken,
+  expectFailure,
+  findPda,
+  initializePlatform,
+  tokenName,
+} from "./fixtures";
+
+describe("Batched config updates", () => {
+  let platform;
+
+  const updateConfig = (params) =>
+    pg.program.methods
+      .updateConfig(configParams(params))
+      .accounts({
+        platform: platform.address,
+        signer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+  before(async () => {
+    platform = await initializePlatform();
+  });
+
+  it("applies every field of the batch under a new config version", async () => {
+    const before = await pg.program.account.platform.fetch(platform.address);
+
+    await updateConfig({
+      feeInBps: new anchor.BN(200),
+      totalSupply: new anchor.BN("2000000000000000000"),
+      creationFee: new anchor.BN(1_000),
+    });
+
+    const after = await pg.program.account.platform.fetch(platform.address);
+    assert(after.configVersion.eq(before.configVersion.addn(1)));
+    assert(after.feeInBps.eqn(200));
+    assert(after.totalSupply.eq(new anchor.BN("2000000000000000000")));
+    assert(after.creationFee.eqn(1_000));
+    // Fields left out of the batch are untouched
+    assert(after.virtualSol.eq(before.virtualSol));
+
+    // Tokens snapshot the config version they launched under
+    const token = await createToken(platform, tokenName("versioned", platform));
+    const tokenInfo = await pg.program.account.tokenInfo.fetch(token.tokenInfo);
+    assert(tokenInfo.configVersion.eq(after.configVersion));
+  });
+
+  it("bumps the config version on single field changes too", async () => {
+    const before = await pg.program.account.platform.fetch(platform.address);
+
+    await pg.program.methods
+      .changeTreasury(pg.wallet.publicKey)
+      .accounts({
+        platform: platform.address,
+        signer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const after = await pg.program.account.platform.fetch(platform.address);
+    assert(after.configVersion.eq(before.configVersion.addn(1)));
+  });
+
+  it("rejects the whole batch if any field is invalid", async () => {
+    const before = await pg.program.account.platform.fetch(platform.address);
+
+    await expectFailure(
+      updateConfig({ feeInBps: new anchor.BN(300), virtualSol: new anchor.BN(0) }),
+      "ZeroVirtualSol"
+    );
+
+    const after = await pg.program.account.platform.fetch(platform.address);
+    assert(after.configVersion.eq(before.configVersion));
+    assert(after.feeInBps.eq(before.feeInBps));
+  });
+
+  describe("behind a timelock", () => {
+    let timelocked;
+
+    before(async () => {
+      timelocked = await initializePlatform({ timelockDelay: new anchor.BN(60) });
+    });
+
+    it("cancels a queued batch before it executes", async () => {
+      const { pendingChangeNonce } = await pg.program.account.platform.fetch(
+        timelocked.address
+      );
+      const pendingChange = findPda([
+        Buffer.from("pending_change"),
+        timelocked.address.toBuffer(),
+        pendingChangeNonce.toArrayLike(Buffer, "le", 8),
+      ]);
+
+      await pg.program.methods
+        .queueConfigChange(configParams({ feeInBps: new anchor.BN(400) }))
+        .accounts({
+          platform: timelocked.address,
+          pendingChange,
+          signer: pg.wallet.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc();
+
+      // Batches skip the queue only on platforms without a timelock
+      await expectFailure(
+        pg.program.methods
+          .updateConfig(configParams({ feeInBps: new anchor.BN(400) }))
+          .accounts({
+            platform: timelocked.address,
+            signer: pg.wallet.publicKey,
+            systemProgram: web3.SystemProgram.programId,
+          })
+          .rpc(),
+        "TimelockActive"
+      );
+
+      await pg.program.methods
+        .cancelConfigChange()
+        .accounts({
+          platform: timelocked.address,
+          pendingChange,
+          proposer: pg.wallet.publicKey,
+          signer: pg.wallet.publicKey,
+        })
+        .rpc();
+
+      assert.equal(await pg.connection.getAccountInfo(pendingChange), null);
+      const platformAccount = await pg.program.account.platform.fetch(timelocked.address);
+      assert(platformAccount.feeInBps.eqn(100));
+    });
+  });
+});
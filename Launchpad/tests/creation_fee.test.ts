@@ -0,0 +1,67 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  createToken,
+  expectFailure,
+  findPda,
+  initializePlatform,
+  tokenName,
+} from "./fixtures";
+
+describe("Token creation fee", () => {
+  const creationFee = new anchor.BN(web3.LAMPORTS_PER_SOL / 10);
+  let platform;
+
+  before(async () => {
+    platform = await initializePlatform({
+      creationFee,
+      creationFeeWaivedForAllowlisted: true,
+    });
+  });
+
+  it("charges the creation fee to the platform", async () => {
+    const before = await pg.program.account.platform.fetch(platform.address);
+    const lamportsBefore = await pg.connection.getBalance(platform.address);
+
+    await createToken(platform, tokenName("paid", platform));
+
+    const after = await pg.program.account.platform.fetch(platform.address);
+    assert(after.accumulatedFees.sub(before.accumulatedFees).eq(creationFee));
+    assert.equal(
+      (await pg.connection.getBalance(platform.address)) - lamportsBefore,
+      creationFee.toNumber()
+    );
+  });
+
+  it("waives the creation fee for allowlisted creators", async () => {
+    const allowedCreator = findPda([
+      Buffer.from("allowed_creator"),
+      platform.address.toBuffer(),
+      pg.wallet.publicKey.toBuffer(),
+    ]);
+    await pg.program.methods
+      .addAllowedCreator(pg.wallet.publicKey)
+      .accounts({
+        platform: platform.address,
+        allowedCreator,
+        signer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    const before = await pg.program.account.platform.fetch(platform.address);
+
+    await createToken(platform, tokenName("waived", platform), {}, { allowedCreator });
+
+    const after = await pg.program.account.platform.fetch(platform.address);
+    assert(after.accumulatedFees.eq(before.accumulatedFees));
+  });
+
+  it("rejects a creator who can't pay the creation fee", async () => {
+    const expensive = await initializePlatform({
+      creationFee: new anchor.BN("1000000000000000000"),
+    });
+
+    // The system program fails the transfer with `InsufficientFunds`
+    await expectFailure(createToken(expensive, tokenName("unpaid", expensive)), "0x1");
+  });
+});
@@ -0,0 +1,361 @@
+// Fixtures shared by the launchpad tests: web3, anchor, pg and more are globally available
+
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+
+export const TOKEN_METADATA_PROGRAM_ID = new web3.PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+export const TOKEN_PROGRAM_ID = anchor.utils.token.TOKEN_PROGRAM_ID;
+export const TOKEN_2022_PROGRAM_ID = new web3.PublicKey(
+  "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
+);
+
+/**
+ * Derives a PDA of the launchpad program
+ */
+export const findPda = (seeds) =>
+  web3.PublicKey.findProgramAddressSync(seeds, pg.PROGRAM_ID)[0];
+
+/**
+ * Mirrors `utils::get_reservation_hash`
+ */
+export const reservationHash = (value) =>
+  Buffer.from(
+    anchor.utils.sha256.hash(
+      value
+        .split("")
+        .filter((c) => /[\p{L}\p{N}]/u.test(c))
+        .join("")
+        .toLowerCase()
+    ),
+    "hex"
+  );
+
+/**
+ * Fails the test unless the transaction is rejected with `code`
+ */
+export const expectFailure = async (promise, code) => {
+  try {
+    await promise;
+  } catch (err) {
+    assert(String(err).includes(code), String(err));
+    return;
+  }
+  assert.fail(`transaction should have failed with ${code}`);
+};
+
+/**
+ * Returns the compute units consumed by a confirmed transaction
+ */
+export const computeUnits = async (signature) => {
+  await pg.connection.confirmTransaction(signature, "confirmed");
+  const tx = await pg.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  return tx.meta.computeUnitsConsumed;
+};
+
+/**
+ * Derives the associated token account of `owner` for `mint` under `tokenProgram`
+ */
+export const associatedAddress = (mint, owner, tokenProgram = TOKEN_PROGRAM_ID) =>
+  web3.PublicKey.findProgramAddressSync(
+    [owner.toBuffer(), tokenProgram.toBuffer(), mint.toBuffer()],
+    anchor.utils.token.ASSOCIATED_PROGRAM_ID
+  )[0];
+
+/**
+ * Waits for `seconds` of validator time to pass
+ */
+export const sleep = (seconds) =>
+  new Promise((resolve) => setTimeout(resolve, seconds * 1000));
+
+/**
+ * Returns a new keypair funded with `sol` by the wallet
+ */
+export const fundedKeypair = async (sol = 1) => {
+  const keypair = web3.Keypair.generate();
+
+  await pg.program.provider.sendAndConfirm(
+    new web3.Transaction().add(
+      web3.SystemProgram.transfer({
+        fromPubkey: pg.wallet.publicKey,
+        toPubkey: keypair.publicKey,
+        lamports: Math.round(sol * web3.LAMPORTS_PER_SOL),
+      })
+    )
+  );
+
+  return keypair;
+};
+
+/**
+ * Creates an spl mint to quote curves in and funds the wallet with `amount` of it,
+ * returns the mint and the wallet's token account
+ */
+export const createQuoteMint = async (amount) => {
+  const mint = await createMint(
+    pg.connection,
+    pg.wallet.keypair,
+    pg.wallet.publicKey,
+    null,
+    9
+  );
+  const { address } = await getOrCreateAssociatedTokenAccount(
+    pg.connection,
+    pg.wallet.keypair,
+    mint,
+    pg.wallet.publicKey
+  );
+  await mintTo(pg.connection, pg.wallet.keypair, mint, address, pg.wallet.keypair, amount);
+
+  return { mint, walletAccount: address };
+};
+
+// Test files share a validator, keep the platform ids they create apart
+let platformNonce = 0;
+
+/**
+ * Initializes a platform owned by the wallet under a fresh id,
+ * `params` and `accounts` override the defaults
+ */
+export const initializePlatform = async (params = {}, accounts = {}) => {
+  const id = params.id ?? new anchor.BN(Date.now()).muln(100).addn(platformNonce++);
+  const address = findPda([Buffer.from("platform"), id.toArrayLike(Buffer, "le", 8)]);
+
+  await pg.program.methods
+    .initialize({
+      id,
+      owner: pg.wallet.publicKey,
+      treasury: pg.wallet.publicKey,
+      feeInBps: new anchor.BN(100),
+      referralFeeInBps: new anchor.BN(0),
+      totalSupply: new anchor.BN("1000000000000000000"),
+      virtualSol: new anchor.BN(30 * web3.LAMPORTS_PER_SOL),
+      targetPoolBalance: new anchor.BN(85 * web3.LAMPORTS_PER_SOL),
+      timelockDelay: new anchor.BN(0),
+      launchMode: { open: {} },
+      creationFee: new anchor.BN(0),
+      creationFeeWaivedForAllowlisted: false,
+      ...params,
+    })
+    .accounts({
+      platform: address,
+      signer: pg.wallet.publicKey,
+      quoteMint: null,
+      feeVault: null,
+      systemProgram: web3.SystemProgram.programId,
+      tokenProgram: null,
+      ...accounts,
+    })
+    .rpc();
+
+  return { id, address };
+};
+
+/**
+ * Config update leaving everything but `params` unchanged
+ */
+export const configParams = (params = {}) => ({
+  feeInBps: null,
+  feeSchedule: null,
+  referralFeeInBps: null,
+  totalSupply: null,
+  virtualSol: null,
+  targetPoolBalance: null,
+  timelockDelay: null,
+  creationFee: null,
+  creationFeeWaivedForAllowlisted: null,
+  owner: null,
+  treasury: null,
+  launchMode: null,
+  ...params,
+});
+
+/**
+ * Returns a token name unique to `platform`, within the metadata name length
+ */
+export const tokenName = (prefix, { id }) => `${prefix}${id.toString()}`.slice(0, 20);
+
+/**
+ * Returns the addresses of the token launched under `name` on `platform`
+ */
+export const tokenAccounts = (platform, name) => {
+  const mint = findPda([
+    Buffer.from("mint"),
+    platform.address.toBuffer(),
+    Buffer.from(name),
+  ]);
+
+  return {
+    name,
+    mint,
+    metadata: web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    )[0],
+    tokenAccount: findPda([Buffer.from("token_account"), mint.toBuffer()]),
+    tokenInfo: findPda([Buffer.from("token"), mint.toBuffer()]),
+    priceObservations: findPda([Buffer.from("price_observations"), mint.toBuffer()]),
+  };
+};
+
+/**
+ * Launches a native sol quoted token on `platform`,
+ * `params` and `accounts` override the defaults
+ */
+export const createToken = async (platform, name, params = {}, accounts = {}) => {
+  const symbol = params.symbol ?? name.slice(0, 4).toUpperCase();
+  const token = tokenAccounts(platform, name);
+
+  await pg.program.methods
+    .createToken({
+      name,
+      symbol,
+      uri: "https://example.com/token.json",
+      creatorAllocationBps: new anchor.BN(0),
+      vestingCliff: new anchor.BN(0),
+      vestingDuration: new anchor.BN(0),
+      metadataAuthority: { immutable: {} },
+      profile: null,
+      maxHoldingBps: null,
+      ...params,
+    })
+    .accounts({
+      platform: platform.address,
+      signer: pg.wallet.publicKey,
+      metadata: token.metadata,
+      mint: token.mint,
+      tokenAccount: token.tokenAccount,
+      tokenInfo: token.tokenInfo,
+      priceObservations: token.priceObservations,
+      tokenProfile: null,
+      vesting: null,
+      vestingTokenAccount: null,
+      allowedCreator: null,
+      nameReservation: findPda([
+        Buffer.from("reservation"),
+        platform.address.toBuffer(),
+        reservationHash(name),
+      ]),
+      symbolReservation: findPda([
+        Buffer.from("reservation"),
+        platform.address.toBuffer(),
+        reservationHash(symbol),
+      ]),
+      quoteMint: null,
+      quoteVault: null,
+      collectionMint: null,
+      collectionMetadata: null,
+      collectionMasterEdition: null,
+      systemProgram: web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      rent: web3.SYSVAR_RENT_PUBKEY,
+      ...accounts,
+    })
+    .preInstructions([
+      web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ])
+    .rpc();
+
+  return token;
+};
+
+/**
+ * Launches a native sol quoted Token-2022 token on `platform` withholding `transferFee`
+ * on every transfer, `params` and `accounts` override the defaults
+ */
+export const createToken2022 = async (
+  platform,
+  name,
+  transferFee = null,
+  params = {},
+  accounts = {}
+) => {
+  const symbol = params.symbol ?? name.slice(0, 4).toUpperCase();
+  const token = tokenAccounts(platform, name);
+
+  await pg.program.methods
+    .createToken2022(
+      {
+        name,
+        symbol,
+        uri: "https://example.com/token.json",
+        creatorAllocationBps: new anchor.BN(0),
+        vestingCliff: new anchor.BN(0),
+        vestingDuration: new anchor.BN(0),
+        metadataAuthority: { immutable: {} },
+        profile: null,
+        maxHoldingBps: null,
+        ...params,
+      },
+      transferFee
+    )
+    .accounts({
+      platform: platform.address,
+      signer: pg.wallet.publicKey,
+      mint: token.mint,
+      tokenAccount: token.tokenAccount,
+      tokenInfo: token.tokenInfo,
+      priceObservations: token.priceObservations,
+      tokenProfile: null,
+      vesting: null,
+      vestingTokenAccount: null,
+      allowedCreator: null,
+      nameReservation: findPda([
+        Buffer.from("reservation"),
+        platform.address.toBuffer(),
+        reservationHash(name),
+      ]),
+      symbolReservation: findPda([
+        Buffer.from("reservation"),
+        platform.address.toBuffer(),
+        reservationHash(symbol),
+      ]),
+      quoteMint: null,
+      quoteVault: null,
+      systemProgram: web3.SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      quoteTokenProgram: null,
+      ...accounts,
+    })
+    .preInstructions([
+      web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ])
+    .rpc();
+
+  return token;
+};
+
+/**
+ * Accounts of a native sol quoted buy or sell of `token` on `platform` by the wallet
+ */
+export const tradeAccounts = (platform, { mint, tokenInfo }, accounts = {}) => ({
+  platform: platform.address,
+  signer: pg.wallet.publicKey,
+  mint,
+  tokenInfo,
+  sourceTokenAccount: findPda([Buffer.from("token_account"), mint.toBuffer()]),
+  priceObservations: findPda([Buffer.from("price_observations"), mint.toBuffer()]),
+  userTokenAccount: associatedAddress(
+    mint,
+    pg.wallet.publicKey,
+    accounts.tokenProgram ?? TOKEN_PROGRAM_ID
+  ),
+  referral: null,
+  quoteMint: null,
+  userQuoteAccount: null,
+  quoteVault: null,
+  feeVault: null,
+  systemProgram: web3.SystemProgram.programId,
+  tokenProgram: TOKEN_PROGRAM_ID,
+  quoteTokenProgram: null,
+  associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+  ...accounts,
+});
@@ -0,0 +1,88 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  createToken,
+  expectFailure,
+  findPda,
+  initializePlatform,
+  tokenName,
+} from "./fixtures";
+
+describe("Launch modes", () => {
+  describe("with an allowlist", () => {
+    let platform;
+    let allowedCreator;
+
+    before(async () => {
+      platform = await initializePlatform({ launchMode: { allowlisted: {} } });
+      allowedCreator = findPda([
+        Buffer.from("allowed_creator"),
+        platform.address.toBuffer(),
+        pg.wallet.publicKey.toBuffer(),
+      ]);
+    });
+
+    it("rejects creators missing from the allowlist", async () => {
+      await expectFailure(
+        createToken(platform, tokenName("unlisted", platform)),
+        "CreatorNotAllowed"
+      );
+    });
+
+    it("lets allowlisted creators launch until they are removed", async () => {
+      await pg.program.methods
+        .addAllowedCreator(pg.wallet.publicKey)
+        .accounts({
+          platform: platform.address,
+          allowedCreator,
+          signer: pg.wallet.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc();
+
+      await createToken(platform, tokenName("listed", platform), {}, { allowedCreator });
+
+      await pg.program.methods
+        .removeAllowedCreator()
+        .accounts({
+          platform: platform.address,
+          allowedCreator,
+          signer: pg.wallet.publicKey,
+        })
+        .rpc();
+
+      await expectFailure(
+        createToken(platform, tokenName("delisted", platform)),
+        "CreatorNotAllowed"
+      );
+    });
+  });
+
+  describe("restricted to the owner", () => {
+    it("lets the owner launch", async () => {
+      const platform = await initializePlatform();
+      await pg.program.methods
+        .changeLaunchMode({ adminOnly: {} })
+        .accounts({
+          platform: platform.address,
+          signer: pg.wallet.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc();
+
+      await createToken(platform, tokenName("admin", platform));
+    });
+
+    it("rejects anyone but the owner", async () => {
+      const platform = await initializePlatform({
+        owner: web3.Keypair.generate().publicKey,
+        launchMode: { adminOnly: {} },
+      });
+
+      await expectFailure(
+        createToken(platform, tokenName("notadmin", platform)),
+        "CreatorNotAllowed"
+      );
+    });
+  });
+});
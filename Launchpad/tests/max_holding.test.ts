@@ -0,0 +1,49 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  createToken,
+  expectFailure,
+  initializePlatform,
+  tokenName,
+  tradeAccounts,
+} from "./fixtures";
+
+describe("Max holding per wallet", () => {
+  let platform;
+  let token;
+
+  before(async () => {
+    platform = await initializePlatform();
+
+    // 1% of the supply per wallet
+    token = await createToken(platform, tokenName("capped", platform), {
+      maxHoldingBps: new anchor.BN(100),
+    });
+  });
+
+  it("rejects an out of range max holding", async () => {
+    await expectFailure(
+      createToken(platform, tokenName("invalid", platform), {
+        maxHoldingBps: new anchor.BN(0),
+      }),
+      "InvalidMaxHolding"
+    );
+  });
+
+  it("allows buys below the max holding", async () => {
+    await pg.program.methods
+      .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 100))
+      .accounts(tradeAccounts(platform, token))
+      .rpc();
+  });
+
+  it("rejects buys above the max holding", async () => {
+    await expectFailure(
+      pg.program.methods
+        .buyTokens(new anchor.BN(5 * web3.LAMPORTS_PER_SOL))
+        .accounts(tradeAccounts(platform, token))
+        .rpc(),
+      "MaxHoldingExceeded"
+    );
+  });
+});
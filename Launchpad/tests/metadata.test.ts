@@ -0,0 +1,88 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  TOKEN_METADATA_PROGRAM_ID,
+  createToken,
+  expectFailure,
+  initializePlatform,
+  tokenName,
+} from "./fixtures";
+
+describe("Program managed metadata", () => {
+  let platform;
+  let token;
+  let otherToken;
+
+  const metadataAccounts = ({ mint, metadata }, { tokenInfo } = token) => ({
+    signer: pg.wallet.publicKey,
+    mint,
+    tokenInfo,
+    metadata,
+    tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+  });
+
+  before(async () => {
+    platform = await initializePlatform();
+
+    token = await createToken(platform, tokenName("meta", platform), {
+      metadataAuthority: { program: {} },
+    });
+    otherToken = await createToken(platform, tokenName("othermeta", platform), {
+      metadataAuthority: { program: {} },
+    });
+  });
+
+  it("updates the metadata from the mint alone", async () => {
+    const signature = await pg.program.methods
+      .updateTokenMetadata("META2", "https://example.com/token-v2.json")
+      .accounts(metadataAccounts(token))
+      .rpc();
+    await pg.connection.confirmTransaction(signature, "confirmed");
+
+    const tx = await pg.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const events = new anchor.EventParser(pg.PROGRAM_ID, pg.program.coder).parseLogs(
+      tx.meta.logMessages
+    );
+    const updated = [...events].find((e) => e.name === "tokenMetadataUpdated");
+    assert(updated.data.token.equals(token.mint));
+    assert.equal(updated.data.symbol, "META2");
+  });
+
+  it("rejects a token info of another mint", async () => {
+    await expectFailure(
+      pg.program.methods
+        .updateTokenMetadata("META3", null)
+        .accounts(metadataAccounts(token, otherToken))
+        .rpc(),
+      "ConstraintSeeds"
+    );
+  });
+
+  it("rejects updates from anyone but the creator", async () => {
+    const stranger = web3.Keypair.generate();
+
+    await expectFailure(
+      pg.program.methods
+        .lockMetadata()
+        .accounts({ ...metadataAccounts(token), signer: stranger.publicKey })
+        .signers([stranger])
+        .rpc(),
+      "NotCreator"
+    );
+  });
+
+  it("rejects updates once the metadata is locked", async () => {
+    await pg.program.methods.lockMetadata().accounts(metadataAccounts(token)).rpc();
+
+    await expectFailure(
+      pg.program.methods
+        .updateTokenMetadata("META3", null)
+        .accounts(metadataAccounts(token))
+        .rpc(),
+      "MetadataLocked"
+    );
+  });
+});
@@ -0,0 +1,65 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import { findPda } from "./fixtures";
+
+// Size of the `PlatformV0` layout, see `migrations::PlatformV0::SIZE`
+const PLATFORM_V0_SIZE = 8 + 32 + 8 * 5;
+// Offset of `PlatformV0::accumulated_fees`
+const PLATFORM_V0_ACCUMULATED_FEES_OFFSET = 8 + 32 + 8;
+
+// Only runs against a cluster holding a legacy singleton platform owned by the wallet
+describe("Legacy platform migration", () => {
+  const platform = findPda([Buffer.from("platform")]);
+
+  before(async function () {
+    const account = await pg.connection.getAccountInfo(platform);
+    if (!account || account.data.length !== PLATFORM_V0_SIZE) {
+      this.skip();
+    }
+  });
+
+  it("keeps the accumulated fees withdrawable after the migration", async () => {
+    const legacy = await pg.connection.getAccountInfo(platform);
+    const accumulatedFees = legacy.data.readBigUInt64LE(
+      PLATFORM_V0_ACCUMULATED_FEES_OFFSET
+    );
+    const signerBalance = await pg.connection.getBalance(pg.wallet.publicKey);
+
+    await pg.program.methods
+      .migratePlatform()
+      .accounts({
+        platform,
+        signer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    // The signer pays for the grown account, the fees stay on top of the new rent
+    const migrated = await pg.connection.getAccountInfo(platform);
+    const minimumBalance = await pg.connection.getMinimumBalanceForRentExemption(
+      migrated.data.length
+    );
+    assert(BigInt(migrated.lamports) >= BigInt(minimumBalance) + accumulatedFees);
+    assert((await pg.connection.getBalance(pg.wallet.publicKey)) < signerBalance);
+
+    const audit = await pg.program.methods
+      .auditPlatform()
+      .accounts({ platform, feeVault: null })
+      .simulate();
+    const platformAudited = audit.events.find((e) => e.name === "platformAudited");
+    assert(platformAudited.data.shortfall.isZero());
+
+    await pg.program.methods
+      .withdrawFees(null)
+      .accounts({
+        platform,
+        signer: pg.wallet.publicKey,
+        treasury: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const platformAccount = await pg.program.account.platform.fetch(platform);
+    assert(platformAccount.accumulatedFees.isZero());
+  });
+});
@@ -0,0 +1,63 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  TOKEN_PROGRAM_ID,
+  createToken,
+  createToken2022,
+  expectFailure,
+  initializePlatform,
+  tokenName,
+  tradeAccounts,
+} from "./fixtures";
+
+describe("Mint authority revocation", () => {
+  let platform;
+
+  const mintInfo = async (mint) =>
+    (await pg.connection.getParsedAccountInfo(mint)).value.data.parsed.info;
+
+  before(async () => {
+    platform = await initializePlatform();
+  });
+
+  it("revokes both authorities of spl and token-2022 mints", async () => {
+    const token = await createToken(platform, tokenName("revoked", platform));
+    const token2022 = await createToken2022(platform, tokenName("revoked22", platform));
+
+    for (const { mint } of [token, token2022]) {
+      const info = await mintInfo(mint);
+      assert.equal(info.mintAuthority, null);
+      assert.equal(info.freezeAuthority, null);
+      assert.equal(info.supply, "1000000000000000000");
+    }
+  });
+
+  it("rejects minting past the fixed supply", async () => {
+    const token = await createToken(platform, tokenName("fixed", platform));
+    const { userTokenAccount } = tradeAccounts(platform, token);
+    await pg.program.methods
+      .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 100))
+      .accounts(tradeAccounts(platform, token))
+      .rpc();
+
+    // `MintTo` of the token program, signed by the former authority
+    const data = Buffer.alloc(9);
+    data.writeUInt8(7, 0);
+    data.writeBigUInt64LE(BigInt(1), 1);
+    const mintTo = new web3.TransactionInstruction({
+      programId: TOKEN_PROGRAM_ID,
+      keys: [
+        { pubkey: token.mint, isSigner: false, isWritable: true },
+        { pubkey: userTokenAccount, isSigner: false, isWritable: true },
+        { pubkey: pg.wallet.publicKey, isSigner: true, isWritable: false },
+      ],
+      data,
+    });
+
+    // The token program fails with `FixedSupply` once the mint authority is gone
+    await expectFailure(
+      pg.program.provider.sendAndConfirm(new web3.Transaction().add(mintTo)),
+      "0x5"
+    );
+  });
+});
@@ -0,0 +1,84 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  createToken,
+  expectFailure,
+  initializePlatform,
+  tokenName,
+  tradeAccounts,
+} from "./fixtures";
+
+describe("Platform scoped addresses", () => {
+  let platform;
+  let otherPlatform;
+  let token;
+
+  before(async () => {
+    platform = await initializePlatform();
+    otherPlatform = await initializePlatform();
+
+    token = await createToken(platform, tokenName("scoped", platform));
+  });
+
+  it("rejects the id reserved for the legacy platform", async () => {
+    await expectFailure(
+      initializePlatform({ id: new anchor.BN(0) }),
+      "InvalidPlatformId"
+    );
+  });
+
+  it("lets two platforms launch the same name", async () => {
+    const twin = await createToken(otherPlatform, token.name);
+
+    assert(!twin.mint.equals(token.mint));
+    const tokenInfo = await pg.program.account.tokenInfo.fetch(twin.tokenInfo);
+    assert(tokenInfo.platform.equals(otherPlatform.address));
+  });
+
+  it("rejects an account other than a platform", async () => {
+    await expectFailure(
+      pg.program.methods
+        .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 10))
+        .accounts(tradeAccounts({ address: token.tokenInfo }, token))
+        .rpc(),
+      "AccountDiscriminatorMismatch"
+    );
+  });
+
+  it("rejects trading a token through another platform", async () => {
+    await expectFailure(
+      pg.program.methods
+        .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 10))
+        .accounts(tradeAccounts(otherPlatform, token))
+        .rpc(),
+      "PlatformMismatch"
+    );
+  });
+
+  it("keeps the config and owner of each platform apart", async () => {
+    const otherOwner = web3.Keypair.generate();
+    const tenant = await initializePlatform({
+      owner: otherOwner.publicKey,
+      feeInBps: new anchor.BN(300),
+    });
+    const changeFees = (platform, signer) =>
+      pg.program.methods
+        .changeFees(new anchor.BN(50))
+        .accounts({
+          platform: platform.address,
+          signer: signer.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([signer]);
+
+    // Owning one platform grants nothing on another
+    await expectFailure(changeFees(platform, otherOwner).rpc(), "NotOwner");
+    await changeFees(tenant, otherOwner).rpc();
+
+    const [platformAccount, tenantAccount] =
+      await pg.program.account.platform.fetchMultiple([platform.address, tenant.address]);
+    assert(platformAccount.feeInBps.eqn(100));
+    assert(tenantAccount.feeInBps.eqn(50));
+    assert(tenantAccount.owner.equals(otherOwner.publicKey));
+  });
+});
@@ -0,0 +1,149 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  createToken,
+  initializePlatform,
+  sleep,
+  tokenName,
+  tradeAccounts,
+} from "./fixtures";
+
+// Mirrors `constants::general::MAX_PRICE_OBSERVATIONS`
+const MAX_PRICE_OBSERVATIONS = 32;
+
+describe("Price observations", () => {
+  let platform;
+  let token;
+
+  const buy = () =>
+    pg.program.methods
+      .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 1_000))
+      .accounts(tradeAccounts(platform, token));
+
+  const fetchState = async () => ({
+    tokenInfo: await pg.program.account.tokenInfo.fetch(token.tokenInfo),
+    priceObservations: await pg.program.account.priceObservations.fetch(
+      token.priceObservations
+    ),
+  });
+
+  /**
+   * Mirrors `TokenInfo::price`, the Q64.64 curve price
+   */
+  const price = ({ solReserve, tokenReserve }) => solReserve.shln(64).div(tokenReserve);
+
+  before(async () => {
+    platform = await initializePlatform();
+    token = await createToken(platform, tokenName("twap", platform));
+  });
+
+  it("starts from the creation time with nothing accrued", async () => {
+    const { tokenInfo, priceObservations } = await fetchState();
+
+    assert(tokenInfo.priceCumulative.isZero());
+    assert.equal(priceObservations.index, 0);
+    assert(priceObservations.observations[0].timestamp.eq(tokenInfo.lastPriceUpdate));
+    assert(priceObservations.observations[0].priceCumulative.isZero());
+  });
+
+  it("accrues the price held since the last trade", async () => {
+    const before = await fetchState();
+
+    await sleep(2);
+    await buy().rpc();
+
+    // The cumulative price is extrapolated over the elapsed seconds at the price before the trade
+    const after = await fetchState();
+    const elapsed = after.tokenInfo.lastPriceUpdate.sub(before.tokenInfo.lastPriceUpdate);
+    assert(elapsed.gtn(0));
+    assert(
+      after.tokenInfo.priceCumulative.eq(
+        before.tokenInfo.priceCumulative.add(price(before.tokenInfo).mul(elapsed))
+      )
+    );
+
+    const latest = after.priceObservations.observations[after.priceObservations.index];
+    assert.equal(after.priceObservations.index, before.priceObservations.index + 1);
+    assert(latest.timestamp.eq(after.tokenInfo.lastPriceUpdate));
+    assert(latest.priceCumulative.eq(after.tokenInfo.priceCumulative));
+  });
+
+  it("records a single observation per timestamp", async () => {
+    await sleep(1);
+    const before = await fetchState();
+
+    // Both trades land in the same transaction, so at the same timestamp
+    const tx = new web3.Transaction()
+      .add(await buy().instruction())
+      .add(await buy().instruction());
+    await pg.program.provider.sendAndConfirm(tx);
+
+    const after = await fetchState();
+    assert.equal(after.priceObservations.index, before.priceObservations.index + 1);
+  });
+
+  it("records an observation without trading", async () => {
+    await sleep(1);
+    const before = await fetchState();
+
+    await sleep(1);
+    await pg.program.methods
+      .observePrice()
+      .accounts({
+        tokenInfo: token.tokenInfo,
+        priceObservations: token.priceObservations,
+      })
+      .rpc();
+
+    // The price held since the last trade is accrued, the curve itself is untouched
+    const after = await fetchState();
+    const elapsed = after.tokenInfo.lastPriceUpdate.sub(before.tokenInfo.lastPriceUpdate);
+    assert(elapsed.gtn(0));
+    assert(
+      after.tokenInfo.priceCumulative.eq(
+        before.tokenInfo.priceCumulative.add(price(before.tokenInfo).mul(elapsed))
+      )
+    );
+    assert(after.tokenInfo.solReserve.eq(before.tokenInfo.solReserve));
+    assert(after.tokenInfo.tokenReserve.eq(before.tokenInfo.tokenReserve));
+
+    const latest = after.priceObservations.observations[after.priceObservations.index];
+    assert.equal(
+      after.priceObservations.index,
+      (before.priceObservations.index + 1) % MAX_PRICE_OBSERVATIONS
+    );
+    assert(latest.timestamp.eq(after.tokenInfo.lastPriceUpdate));
+    assert(latest.priceCumulative.eq(after.tokenInfo.priceCumulative));
+  });
+
+  it("overwrites the oldest observations once the buffer is full", async function () {
+    this.timeout((MAX_PRICE_OBSERVATIONS + 8) * 3_000);
+
+    // Trade until every slot is written and the buffer wraps past its first slot
+    let { index: lastIndex } = (await fetchState()).priceObservations;
+    let recorded = lastIndex;
+    while (recorded <= MAX_PRICE_OBSERVATIONS) {
+      await sleep(1);
+      await buy().rpc();
+
+      const { index } = (await fetchState()).priceObservations;
+      recorded += (index - lastIndex + MAX_PRICE_OBSERVATIONS) % MAX_PRICE_OBSERVATIONS;
+      lastIndex = index;
+    }
+
+    const after = await fetchState();
+    assert.equal(after.priceObservations.index, recorded % MAX_PRICE_OBSERVATIONS);
+
+    // Read from the oldest slot onwards, the observations are strictly increasing in time
+    const { index, observations } = after.priceObservations;
+    const ordered = [...observations.slice(index + 1), ...observations.slice(0, index + 1)];
+    ordered.slice(1).forEach((observation, i) => {
+      assert(observation.timestamp.gt(ordered[i].timestamp));
+      assert(observation.priceCumulative.gt(ordered[i].priceCumulative));
+    });
+
+    // The creation observation is gone, the latest one matches the token info
+    assert(!ordered[0].priceCumulative.isZero());
+    assert(ordered[ordered.length - 1].timestamp.eq(after.tokenInfo.lastPriceUpdate));
+  });
+});
@@ -0,0 +1,90 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  configParams,
+  createToken,
+  expectFailure,
+  initializePlatform,
+  tokenName,
+  tradeAccounts,
+} from "./fixtures";
+
+describe("Trade quotes", () => {
+  let platform;
+  let otherPlatform;
+  let token;
+
+  const quoteAccounts = (platform, { mint, tokenInfo }) => ({
+    platform: platform.address,
+    mint,
+    tokenInfo,
+  });
+
+  before(async () => {
+    platform = await initializePlatform();
+    otherPlatform = await initializePlatform();
+
+    // The schedule lifts the fee from the base 1% to 2.5% from the start of the curve
+    await pg.program.methods
+      .updateConfig(
+        configParams({
+          feeSchedule: {
+            kind: { curveProgress: {} },
+            tiers: [{ threshold: new anchor.BN(0), feeInBps: new anchor.BN(250) }],
+          },
+        })
+      )
+      .accounts({
+        platform: platform.address,
+        signer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    token = await createToken(platform, tokenName("quote", platform));
+  });
+
+  it("quotes a buy with the scheduled fee", async () => {
+    const solAmount = new anchor.BN(web3.LAMPORTS_PER_SOL / 10);
+    const quote = await pg.program.methods
+      .quoteBuy(solAmount)
+      .accounts(quoteAccounts(platform, token))
+      .view();
+
+    assert(quote.feeInBps.eqn(250));
+    assert(quote.amountIn.eq(solAmount));
+
+    // The quote leaves the curve untouched and matches the buy that follows it
+    await pg.program.methods
+      .buyTokens(solAmount)
+      .accounts(tradeAccounts(platform, token))
+      .rpc();
+
+    const balance = await pg.connection.getTokenAccountBalance(
+      tradeAccounts(platform, token).userTokenAccount
+    );
+    assert(new anchor.BN(balance.value.amount).eq(quote.amountOut));
+  });
+
+  it("quotes a sell with the scheduled fee", async () => {
+    const tokenAmount = new anchor.BN(1_000_000);
+    const quote = await pg.program.methods
+      .quoteSell(tokenAmount)
+      .accounts(quoteAccounts(platform, token))
+      .view();
+
+    assert(quote.feeInBps.eqn(250));
+    assert(quote.amountIn.eq(tokenAmount));
+    assert(quote.feeAmount.gtn(0));
+  });
+
+  it("rejects quoting a token of another platform", async () => {
+    await expectFailure(
+      pg.program.methods
+        .quoteBuy(new anchor.BN(web3.LAMPORTS_PER_SOL / 10))
+        .accounts(quoteAccounts(otherPlatform, token))
+        .view(),
+      "PlatformMismatch"
+    );
+  });
+});
@@ -0,0 +1,174 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  TOKEN_PROGRAM_ID,
+  createQuoteMint,
+  createToken,
+  associatedAddress,
+  expectFailure,
+  findPda,
+  fundedKeypair,
+  initializePlatform,
+  tokenAccounts,
+  tokenName,
+  tradeAccounts,
+} from "./fixtures";
+
+describe("Spl quoted curves", () => {
+  let quote;
+  let platform;
+  let feeVault;
+  let token;
+  let quoteVault;
+
+  const balance = async (account) =>
+    new anchor.BN((await pg.connection.getTokenAccountBalance(account)).value.amount);
+
+  const quoteTradeAccounts = (accounts = {}) =>
+    tradeAccounts(platform, token, {
+      quoteMint: quote.mint,
+      userQuoteAccount: quote.walletAccount,
+      quoteVault,
+      feeVault,
+      quoteTokenProgram: TOKEN_PROGRAM_ID,
+      ...accounts,
+    });
+
+  before(async () => {
+    quote = await createQuoteMint(BigInt(1_000) * BigInt(web3.LAMPORTS_PER_SOL));
+
+    const id = new anchor.BN(Date.now()).muln(100).addn(99);
+    const address = findPda([Buffer.from("platform"), id.toArrayLike(Buffer, "le", 8)]);
+    feeVault = findPda([Buffer.from("fee_vault"), address.toBuffer()]);
+    platform = await initializePlatform(
+      { id },
+      { quoteMint: quote.mint, feeVault, tokenProgram: TOKEN_PROGRAM_ID }
+    );
+
+    const name = tokenName("quoted", platform);
+    quoteVault = findPda([
+      Buffer.from("quote_vault"),
+      tokenAccounts(platform, name).mint.toBuffer(),
+    ]);
+    token = await createToken(
+      platform,
+      name,
+      {},
+      { quoteMint: quote.mint, quoteVault }
+    );
+  });
+
+  it("rejects launching without a quote vault", async () => {
+    await expectFailure(
+      createToken(platform, tokenName("unvaulted", platform)),
+      "QuoteAccountsMissing"
+    );
+  });
+
+  it("holds the curve reserve and the fees in the quote token", async () => {
+    const amount = new anchor.BN(web3.LAMPORTS_PER_SOL);
+    const walletBefore = await balance(quote.walletAccount);
+
+    await pg.program.methods.buyTokens(amount).accounts(quoteTradeAccounts()).rpc();
+
+    const platformAccount = await pg.program.account.platform.fetch(platform.address);
+    const fees = platformAccount.accumulatedQuoteFees;
+    assert(fees.gtn(0));
+    assert(walletBefore.sub(await balance(quote.walletAccount)).eq(amount));
+    assert((await balance(quoteVault)).eq(amount.sub(fees)));
+    assert((await balance(feeVault)).eq(fees));
+  });
+
+  it("rejects buying without the quote accounts", async () => {
+    await expectFailure(
+      pg.program.methods
+        .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL))
+        .accounts(quoteTradeAccounts({ quoteVault: null }))
+        .rpc(),
+      "QuoteAccountsMissing"
+    );
+  });
+
+  it("pays sells out of the quote vault", async () => {
+    const userTokenAccount = associatedAddress(token.mint, pg.wallet.publicKey);
+    const tokenAmount = (await balance(userTokenAccount)).divn(2);
+    const walletBefore = await balance(quote.walletAccount);
+    const vaultBefore = await balance(quoteVault);
+
+    await pg.program.methods.sellTokens(tokenAmount).accounts(quoteTradeAccounts()).rpc();
+
+    const received = (await balance(quote.walletAccount)).sub(walletBefore);
+    assert(received.gtn(0));
+    assert(vaultBefore.sub(await balance(quoteVault)).gt(received));
+  });
+
+  it("rejects a quote mint other than the platform's", async () => {
+    const other = await createQuoteMint(BigInt(web3.LAMPORTS_PER_SOL));
+
+    await expectFailure(
+      pg.program.methods
+        .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL))
+        .accounts(quoteTradeAccounts({ quoteMint: other.mint }))
+        .rpc(),
+      "ConstraintAddress"
+    );
+  });
+
+  it("rejects referrals, whose balances are held in sol", async () => {
+    const referrer = await fundedKeypair(0.1);
+    const referral = findPda([Buffer.from("referral"), referrer.publicKey.toBuffer()]);
+    await pg.program.methods
+      .registerReferrer()
+      .accounts({
+        referral,
+        signer: referrer.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([referrer])
+      .rpc();
+
+    await expectFailure(
+      pg.program.methods
+        .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL))
+        .accounts(quoteTradeAccounts({ referral }))
+        .rpc(),
+      "ReferralRequiresNativeQuote"
+    );
+  });
+
+  it("rejects a creation fee, which is collected in sol", async () => {
+    const id = new anchor.BN(Date.now()).muln(100).addn(98);
+    const address = findPda([Buffer.from("platform"), id.toArrayLike(Buffer, "le", 8)]);
+
+    await expectFailure(
+      initializePlatform(
+        { id, creationFee: new anchor.BN(web3.LAMPORTS_PER_SOL / 10) },
+        {
+          quoteMint: quote.mint,
+          feeVault: findPda([Buffer.from("fee_vault"), address.toBuffer()]),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        }
+      ),
+      "CreationFeeRequiresNativeQuote"
+    );
+  });
+
+  it("withdraws the quote fees to the treasury", async () => {
+    const fees = await balance(feeVault);
+
+    await pg.program.methods
+      .withdrawQuoteFees(null)
+      .accounts({
+        platform: platform.address,
+        signer: pg.wallet.publicKey,
+        quoteMint: quote.mint,
+        feeVault,
+        treasuryQuoteAccount: quote.walletAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    assert((await balance(feeVault)).isZero());
+    assert(fees.gtn(0));
+  });
+});
@@ -0,0 +1,102 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  associatedAddress,
+  createToken,
+  expectFailure,
+  findPda,
+  fundedKeypair,
+  initializePlatform,
+  tokenName,
+  tradeAccounts,
+} from "./fixtures";
+
+describe("Referral fee sharing", () => {
+  const feeInBps = 100;
+  const referralFeeInBps = 2_000;
+  let platform;
+  let token;
+  let referrer;
+  let referral;
+
+  before(async () => {
+    platform = await initializePlatform({
+      feeInBps: new anchor.BN(feeInBps),
+      referralFeeInBps: new anchor.BN(referralFeeInBps),
+    });
+    token = await createToken(platform, tokenName("referral", platform));
+
+    referrer = await fundedKeypair(0.2);
+    referral = findPda([Buffer.from("referral"), referrer.publicKey.toBuffer()]);
+
+    await pg.program.methods
+      .registerReferrer()
+      .accounts({
+        referral,
+        signer: referrer.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([referrer])
+      .rpc();
+  });
+
+  it("carves the referrer's share out of the protocol fee", async () => {
+    const solAmount = new anchor.BN(web3.LAMPORTS_PER_SOL / 10);
+    const before = await pg.program.account.platform.fetch(platform.address);
+
+    await pg.program.methods
+      .buyTokens(solAmount)
+      .accounts(tradeAccounts(platform, token, { referral }))
+      .rpc();
+
+    // Mirrors `utils::calculate_buy_fee` and `utils::calculate_referral_fee`
+    const fee = solAmount.muln(feeInBps).divn(10_000 + feeInBps);
+    const referralFee = fee.muln(referralFeeInBps).divn(10_000);
+
+    const referralAccount = await pg.program.account.referral.fetch(referral);
+    assert(referralAccount.unclaimedFees.eq(referralFee));
+    assert(referralAccount.referralVolume.eq(solAmount));
+    assert(referralAccount.tradeCount.eqn(1));
+
+    const after = await pg.program.account.platform.fetch(platform.address);
+    assert(after.accumulatedFees.sub(before.accumulatedFees).eq(fee.sub(referralFee)));
+  });
+
+  it("rejects referring your own trades", async () => {
+    await expectFailure(
+      pg.program.methods
+        .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 100))
+        .accounts(
+          tradeAccounts(platform, token, {
+            signer: referrer.publicKey,
+            userTokenAccount: associatedAddress(token.mint, referrer.publicKey),
+            referral,
+          })
+        )
+        .signers([referrer])
+        .rpc(),
+      "SelfReferral"
+    );
+  });
+
+  it("pays out the unclaimed fees once", async () => {
+    const { unclaimedFees } = await pg.program.account.referral.fetch(referral);
+    const claim = () =>
+      pg.program.methods
+        .claimReferralFees()
+        .accounts({
+          referral,
+          signer: referrer.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([referrer])
+        .rpc();
+
+    const before = await pg.connection.getBalance(referral);
+    await claim();
+
+    assert.equal(before - (await pg.connection.getBalance(referral)), unclaimedFees.toNumber());
+    assert((await pg.program.account.referral.fetch(referral)).unclaimedFees.isZero());
+    await expectFailure(claim(), "NothingToClaim");
+  });
+});
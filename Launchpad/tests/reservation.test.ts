@@ -0,0 +1,65 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  createToken,
+  expectFailure,
+  findPda,
+  initializePlatform,
+  reservationHash,
+  tokenName,
+} from "./fixtures";
+
+describe("Name and symbol reservations", () => {
+  let platform;
+
+  const reservation = (value) =>
+    findPda([Buffer.from("reservation"), platform.address.toBuffer(), reservationHash(value)]);
+
+  const addReservation = (value, creator = null) =>
+    pg.program.methods
+      .addReservation(value, creator)
+      .accounts({
+        platform: platform.address,
+        reservation: reservation(value),
+        signer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+  before(async () => {
+    platform = await initializePlatform();
+  });
+
+  it("rejects lookalikes of a blocked symbol", async () => {
+    await addReservation("USDC");
+
+    await expectFailure(
+      createToken(platform, tokenName("fakeusd", platform), { symbol: "u.s.d.c" }),
+      "NameReserved"
+    );
+  });
+
+  it("lets only the assigned creator launch a reserved name", async () => {
+    const name = tokenName("reserved", platform);
+    await addReservation(name, pg.wallet.publicKey);
+
+    await createToken(platform, name);
+  });
+
+  it("releases a name once its reservation is removed", async () => {
+    const name = tokenName("released", platform);
+    await addReservation(name, web3.Keypair.generate().publicKey);
+    await expectFailure(createToken(platform, name), "NameReserved");
+
+    await pg.program.methods
+      .removeReservation()
+      .accounts({
+        platform: platform.address,
+        reservation: reservation(name),
+        signer: pg.wallet.publicKey,
+      })
+      .rpc();
+
+    await createToken(platform, name);
+  });
+});
@@ -0,0 +1,364 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  associatedAddress,
+  createQuoteMint,
+  createToken,
+  createToken2022,
+  expectFailure,
+  findPda,
+  fundedKeypair,
+  initializePlatform,
+  tokenAccounts,
+  tokenName,
+  tradeAccounts,
+} from "./fixtures";
+
+describe("Swaps and batched buys", () => {
+  let platform;
+  let tokenA;
+  let tokenB;
+
+  before(async () => {
+    platform = await initializePlatform();
+
+    tokenA = await createToken(platform, tokenName("swapa", platform));
+    tokenB = await createToken(platform, tokenName("swapb", platform));
+
+    await pg.program.methods
+      .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 10))
+      .accounts(tradeAccounts(platform, tokenA))
+      .rpc();
+  });
+
+  const swapAccounts = (from, to, buyTokenProgram = TOKEN_PROGRAM_ID, onPlatform = platform) => {
+    const sell = tradeAccounts(onPlatform, from);
+    const buy = tradeAccounts(onPlatform, to, { tokenProgram: buyTokenProgram });
+    return {
+      platform: onPlatform.address,
+      signer: pg.wallet.publicKey,
+      sellMint: sell.mint,
+      sellTokenInfo: sell.tokenInfo,
+      sellSourceTokenAccount: sell.sourceTokenAccount,
+      sellPriceObservations: sell.priceObservations,
+      sellUserTokenAccount: sell.userTokenAccount,
+      buyMint: buy.mint,
+      buyTokenInfo: buy.tokenInfo,
+      buySourceTokenAccount: buy.sourceTokenAccount,
+      buyPriceObservations: buy.priceObservations,
+      buyUserTokenAccount: buy.userTokenAccount,
+      systemProgram: web3.SystemProgram.programId,
+      sellTokenProgram: TOKEN_PROGRAM_ID,
+      buyTokenProgram,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    };
+  };
+
+  it("rejects a swap below the minimum amount out", async () => {
+    await expectFailure(
+      pg.program.methods
+        .swapTokens(new anchor.BN(1_000_000), new anchor.BN("1000000000000000000"))
+        .accounts(swapAccounts(tokenA, tokenB))
+        .rpc(),
+      "SlippageExceeded"
+    );
+  });
+
+  it("rejects swapping a token for itself", async () => {
+    await expectFailure(
+      pg.program.methods
+        .swapTokens(new anchor.BN(1_000_000), new anchor.BN(0))
+        .accounts(swapAccounts(tokenA, tokenA))
+        .rpc(),
+      "SameToken"
+    );
+  });
+
+  it("rejects swapping into a token of another platform", async () => {
+    const otherPlatform = await initializePlatform();
+    const otherToken = await createToken(otherPlatform, tokenName("swapother", otherPlatform));
+
+    await expectFailure(
+      pg.program.methods
+        .swapTokens(new anchor.BN(1_000_000), new anchor.BN(0))
+        .accounts(swapAccounts(tokenA, otherToken))
+        .rpc(),
+      "PlatformMismatch"
+    );
+  });
+
+  it("rejects swapping tokens quoted in an spl token", async () => {
+    const quote = await createQuoteMint(BigInt(web3.LAMPORTS_PER_SOL));
+    const id = new anchor.BN(Date.now()).muln(100).addn(97);
+    const address = findPda([Buffer.from("platform"), id.toArrayLike(Buffer, "le", 8)]);
+    const quotePlatform = await initializePlatform(
+      { id },
+      {
+        quoteMint: quote.mint,
+        feeVault: findPda([Buffer.from("fee_vault"), address.toBuffer()]),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }
+    );
+    const quoted = [];
+    for (const prefix of ["quoteda", "quotedb"]) {
+      const name = tokenName(prefix, quotePlatform);
+      const quoteVault = findPda([
+        Buffer.from("quote_vault"),
+        tokenAccounts(quotePlatform, name).mint.toBuffer(),
+      ]);
+      quoted.push(
+        await createToken(quotePlatform, name, {}, { quoteMint: quote.mint, quoteVault })
+      );
+    }
+
+    // The seller's token account has to exist for the swap to reach the quote check
+    await pg.program.methods
+      .initTokenAccountForToken()
+      .accounts({
+        signer: pg.wallet.publicKey,
+        mint: quoted[0].mint,
+        tokenAccount: associatedAddress(quoted[0].mint, pg.wallet.publicKey),
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      })
+      .rpc();
+
+    await expectFailure(
+      pg.program.methods
+        .swapTokens(new anchor.BN(1_000_000), new anchor.BN(0))
+        .accounts(swapAccounts(quoted[0], quoted[1], TOKEN_PROGRAM_ID, quotePlatform))
+        .rpc(),
+      "NativeQuoteRequired"
+    );
+  });
+
+  it("swaps token A for token B", async () => {
+    const before = await pg.program.account.tokenInfo.fetch(tokenB.tokenInfo);
+
+    await pg.program.methods
+      .swapTokens(new anchor.BN(1_000_000), new anchor.BN(1))
+      .accounts(swapAccounts(tokenA, tokenB))
+      .rpc();
+
+    const after = await pg.program.account.tokenInfo.fetch(tokenB.tokenInfo);
+    assert(after.solReserve.gt(before.solReserve));
+  });
+
+  it("rejects a batch with missing remaining accounts", async () => {
+    await expectFailure(
+      pg.program.methods
+        .buyTokensBatch([
+          { solAmount: new anchor.BN(1_000_000), minTokenAmountOut: new anchor.BN(0) },
+        ])
+        .accounts({
+          platform: platform.address,
+          signer: pg.wallet.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          token2022Program: null,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .rpc(),
+      "InvalidRemainingAccounts"
+    );
+  });
+
+  it("buys both tokens in one batch", async () => {
+    const before = await pg.program.account.tokenInfo.fetchMultiple([
+      tokenA.tokenInfo,
+      tokenB.tokenInfo,
+    ]);
+
+    const remainingAccounts = [tokenA, tokenB].flatMap((token) => {
+      const accounts = tradeAccounts(platform, token);
+      return [
+        { pubkey: accounts.mint, isSigner: false, isWritable: false },
+        { pubkey: accounts.tokenInfo, isSigner: false, isWritable: true },
+        { pubkey: accounts.sourceTokenAccount, isSigner: false, isWritable: true },
+        { pubkey: accounts.userTokenAccount, isSigner: false, isWritable: true },
+        { pubkey: accounts.priceObservations, isSigner: false, isWritable: true },
+      ];
+    });
+
+    await pg.program.methods
+      .buyTokensBatch([
+        { solAmount: new anchor.BN(web3.LAMPORTS_PER_SOL / 10), minTokenAmountOut: new anchor.BN(1) },
+        { solAmount: new anchor.BN(web3.LAMPORTS_PER_SOL / 20), minTokenAmountOut: new anchor.BN(1) },
+      ])
+      .accounts({
+        platform: platform.address,
+        signer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token2022Program: null,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      })
+      .remainingAccounts(remainingAccounts)
+      .rpc();
+
+    const after = await pg.program.account.tokenInfo.fetchMultiple([
+      tokenA.tokenInfo,
+      tokenB.tokenInfo,
+    ]);
+    after.forEach((tokenInfo, i) =>
+      assert(tokenInfo.solReserve.gt(before[i].solReserve))
+    );
+  });
+
+  describe("of a token withholding a transfer fee", () => {
+    let feeToken;
+    let feeTradeAccounts;
+
+    /**
+     * Tokens the wallet receives from buying `solAmount` of the fee token, net of the transfer fee
+     */
+    const quoteReceived = async (solAmount) =>
+      (
+        await pg.program.methods
+          .quoteBuy(solAmount)
+          .accounts({
+            platform: platform.address,
+            mint: feeToken.mint,
+            tokenInfo: feeToken.tokenInfo,
+          })
+          .view()
+      ).amountOut;
+
+    const batchBuy = (solAmount, minTokenAmountOut) =>
+      pg.program.methods
+        .buyTokensBatch([{ solAmount, minTokenAmountOut }])
+        .accounts({
+          platform: platform.address,
+          signer: pg.wallet.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          token2022Program: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: feeTradeAccounts.mint, isSigner: false, isWritable: false },
+          { pubkey: feeTradeAccounts.tokenInfo, isSigner: false, isWritable: true },
+          { pubkey: feeTradeAccounts.sourceTokenAccount, isSigner: false, isWritable: true },
+          { pubkey: feeTradeAccounts.userTokenAccount, isSigner: false, isWritable: true },
+          { pubkey: feeTradeAccounts.priceObservations, isSigner: false, isWritable: true },
+        ])
+        .rpc();
+
+    const balance = async () =>
+      new anchor.BN(
+        (
+          await pg.connection.getTokenAccountBalance(feeTradeAccounts.userTokenAccount)
+        ).value.amount
+      );
+
+    before(async () => {
+      // 5% of every transfer is withheld
+      feeToken = await createToken2022(platform, tokenName("swapfee", platform), {
+        transferFeeBps: 500,
+        maximumTransferFee: new anchor.BN("18446744073709551615"),
+      });
+      feeTradeAccounts = tradeAccounts(platform, feeToken, {
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      });
+
+      // Opens the wallet's token account, so its balance can be read before the batch buys
+      await pg.program.methods
+        .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 100))
+        .accounts(feeTradeAccounts)
+        .rpc();
+    });
+
+    it("rejects a swap whose received amount falls below the minimum", async () => {
+      const tokenAmount = new anchor.BN(1_000_000);
+      const swap = () =>
+        pg.program.methods
+          .swapTokens(tokenAmount, new anchor.BN(1))
+          .accounts(swapAccounts(tokenA, feeToken, TOKEN_2022_PROGRAM_ID));
+
+      // The event reports the amount bought before the transfer fee is withheld
+      const simulation = await swap().simulate();
+      const bought = simulation.events.find((e) => e.name === "tokensSwapped").data.amountOut;
+
+      await expectFailure(
+        pg.program.methods
+          .swapTokens(tokenAmount, bought)
+          .accounts(swapAccounts(tokenA, feeToken, TOKEN_2022_PROGRAM_ID))
+          .rpc(),
+        "SlippageExceeded"
+      );
+      await swap().rpc();
+    });
+
+    it("rejects a batch buy whose received amount falls below the minimum", async () => {
+      const solAmount = new anchor.BN(web3.LAMPORTS_PER_SOL / 10);
+      const received = await quoteReceived(solAmount);
+
+      // The gross amount bought covers this minimum, the amount received doesn't
+      await expectFailure(batchBuy(solAmount, received.addn(1)), "SlippageExceeded");
+    });
+
+    it("buys at the minimum net of the transfer fee", async () => {
+      const solAmount = new anchor.BN(web3.LAMPORTS_PER_SOL / 10);
+      const received = await quoteReceived(solAmount);
+      const before = await balance();
+
+      await batchBuy(solAmount, received);
+
+      assert((await balance()).sub(before).eq(received));
+    });
+
+    it("opens missing token accounts and mixes token programs in one batch", async () => {
+      const buyer = await fundedKeypair(0.5);
+      const tokens = [
+        [tokenB, TOKEN_PROGRAM_ID],
+        [feeToken, TOKEN_2022_PROGRAM_ID],
+      ];
+      const userTokenAccounts = tokens.map(([token, tokenProgram]) =>
+        associatedAddress(token.mint, buyer.publicKey, tokenProgram)
+      );
+
+      const batch = pg.program.methods
+        .buyTokensBatch(
+          tokens.map(() => ({
+            solAmount: new anchor.BN(web3.LAMPORTS_PER_SOL / 100),
+            minTokenAmountOut: new anchor.BN(1),
+          }))
+        )
+        .accounts({
+          platform: platform.address,
+          signer: buyer.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          token2022Program: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .remainingAccounts(
+          tokens.flatMap(([token, tokenProgram], i) => {
+            const accounts = tradeAccounts(platform, token, { tokenProgram });
+            return [
+              { pubkey: accounts.mint, isSigner: false, isWritable: false },
+              { pubkey: accounts.tokenInfo, isSigner: false, isWritable: true },
+              { pubkey: accounts.sourceTokenAccount, isSigner: false, isWritable: true },
+              { pubkey: userTokenAccounts[i], isSigner: false, isWritable: true },
+              { pubkey: accounts.priceObservations, isSigner: false, isWritable: true },
+            ];
+          })
+        )
+        .signers([buyer]);
+
+      // Every buy of the batch is reported on its own
+      const simulation = await batch.simulate();
+      const bought = simulation.events.filter((e) => e.name === "tokensBought");
+      assert.equal(bought.length, tokens.length);
+
+      await batch.rpc();
+      for (const userTokenAccount of userTokenAccounts) {
+        const { value } = await pg.connection.getTokenAccountBalance(userTokenAccount);
+        assert(new anchor.BN(value.amount).gtn(0));
+      }
+    });
+  });
+});
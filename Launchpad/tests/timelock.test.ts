@@ -0,0 +1,206 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  configParams,
+  createToken,
+  expectFailure,
+  findPda,
+  initializePlatform,
+  reservationHash,
+  sleep,
+  tokenName,
+} from "./fixtures";
+
+describe("Timelocked config changes", () => {
+  const timelockDelay = 2;
+  let platform;
+
+  /**
+   * Queues `operation` on the platform and returns the address of the pending change
+   */
+  const queueOperation = async (operation) => {
+    const { pendingChangeNonce } = await pg.program.account.platform.fetch(
+      platform.address
+    );
+    const pendingChange = findPda([
+      Buffer.from("pending_change"),
+      platform.address.toBuffer(),
+      pendingChangeNonce.toArrayLike(Buffer, "le", 8),
+    ]);
+
+    await pg.program.methods
+      .queueOperation(operation)
+      .accounts({
+        platform: platform.address,
+        pendingChange,
+        signer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    return pendingChange;
+  };
+
+  const queueChange = (params) =>
+    queueOperation({ updateConfig: { params: configParams(params) } });
+
+  const executeChange = (pendingChange, accounts = {}) =>
+    pg.program.methods
+      .executeConfigChange()
+      .accounts({
+        platform: platform.address,
+        pendingChange,
+        proposer: pg.wallet.publicKey,
+        signer: pg.wallet.publicKey,
+        reservation: null,
+        removedReservation: null,
+        tokenInfo: null,
+        systemProgram: web3.SystemProgram.programId,
+        ...accounts,
+      })
+      .rpc();
+
+  const reservation = (value) =>
+    findPda([Buffer.from("reservation"), platform.address.toBuffer(), reservationHash(value)]);
+
+  before(async () => {
+    platform = await initializePlatform({ timelockDelay: new anchor.BN(timelockDelay) });
+  });
+
+  it("rejects changing the owner without the timelock", async () => {
+    await expectFailure(
+      pg.program.methods
+        .changeOwner(web3.Keypair.generate().publicKey)
+        .accounts({
+          platform: platform.address,
+          signer: pg.wallet.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc(),
+      "TimelockActive"
+    );
+  });
+
+  it("rejects changing the treasury without the timelock", async () => {
+    await expectFailure(
+      pg.program.methods
+        .changeTreasury(web3.Keypair.generate().publicKey)
+        .accounts({
+          platform: platform.address,
+          signer: pg.wallet.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc(),
+      "TimelockActive"
+    );
+  });
+
+  it("rejects changing the launch mode without the timelock", async () => {
+    await expectFailure(
+      pg.program.methods
+        .changeLaunchMode({ adminOnly: {} })
+        .accounts({
+          platform: platform.address,
+          signer: pg.wallet.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc(),
+      "TimelockActive"
+    );
+  });
+
+  it("rejects reserving a name without the timelock", async () => {
+    await expectFailure(
+      pg.program.methods
+        .addReservation("USDC", null)
+        .accounts({
+          platform: platform.address,
+          reservation: reservation("USDC"),
+          signer: pg.wallet.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc(),
+      "TimelockActive"
+    );
+  });
+
+  it("changes the launch mode once the timelock elapses", async () => {
+    const pendingChange = await queueChange({ launchMode: { allowlisted: {} } });
+
+    await sleep(timelockDelay + 1);
+    await executeChange(pendingChange);
+
+    const platformAccount = await pg.program.account.platform.fetch(platform.address);
+    assert.deepEqual(platformAccount.launchMode, { allowlisted: {} });
+
+    // Open the platform again for the launches below
+    await sleep(timelockDelay + 1);
+    await executeChange(await queueChange({ launchMode: { open: {} } }));
+  });
+
+  it("adds and removes a reservation once the timelock elapses", async () => {
+    const name = tokenName("queued", platform);
+    const added = await queueOperation({ addReservation: { value: name, creator: null } });
+
+    // The reservation account has to be passed to create it
+    await sleep(timelockDelay + 1);
+    await expectFailure(executeChange(added), "OperationAccountsMismatch");
+    await executeChange(added, { reservation: reservation(name) });
+    await expectFailure(createToken(platform, name), "NameReserved");
+
+    const removed = await queueOperation({
+      removeReservation: { hash: [...reservationHash(name)] },
+    });
+    await sleep(timelockDelay + 1);
+    await executeChange(removed, { removedReservation: reservation(name) });
+
+    await createToken(platform, name);
+  });
+
+  it("resumes a token once the timelock elapses", async () => {
+    const token = await createToken(platform, tokenName("resumed", platform));
+
+    await expectFailure(
+      pg.program.methods
+        .resumeToken()
+        .accounts({
+          platform: platform.address,
+          signer: pg.wallet.publicKey,
+          tokenInfo: token.tokenInfo,
+        })
+        .rpc(),
+      "TimelockActive"
+    );
+
+    const pendingChange = await queueOperation({ resumeToken: { token: token.mint } });
+    await sleep(timelockDelay + 1);
+    await executeChange(pendingChange, { tokenInfo: token.tokenInfo });
+
+    const tokenInfo = await pg.program.account.tokenInfo.fetch(token.tokenInfo);
+    assert(!tokenInfo.halted);
+  });
+
+  it("rejects queuing the default pubkey as owner", async () => {
+    await expectFailure(
+      queueChange({ owner: web3.PublicKey.default }),
+      "InvalidOwner"
+    );
+  });
+
+  it("transfers the owner and treasury once the timelock elapses", async () => {
+    const owner = web3.Keypair.generate().publicKey;
+    const treasury = web3.Keypair.generate().publicKey;
+    const pendingChange = await queueChange({ owner, treasury });
+
+    await expectFailure(executeChange(pendingChange), "TimelockNotMature");
+
+    const before = await pg.program.account.platform.fetch(platform.address);
+    await sleep(timelockDelay + 1);
+    await executeChange(pendingChange);
+
+    const platformAccount = await pg.program.account.platform.fetch(platform.address);
+    assert(platformAccount.owner.equals(owner));
+    assert(platformAccount.treasury.equals(treasury));
+    assert(platformAccount.configVersion.eq(before.configVersion.addn(1)));
+  });
+});
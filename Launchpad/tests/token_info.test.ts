@@ -0,0 +1,94 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  createToken,
+  expectFailure,
+  findPda,
+  initializePlatform,
+  tokenName,
+  tradeAccounts,
+} from "./fixtures";
+
+describe("Token info derivation", () => {
+  let platform;
+  let tokenA;
+  let tokenB;
+
+  before(async () => {
+    platform = await initializePlatform();
+
+    tokenA = await createToken(platform, tokenName("alpha", platform));
+    tokenB = await createToken(platform, tokenName("bravo", platform));
+
+    // Hold some of token A so sells reach the token info checks
+    await pg.program.methods
+      .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 10))
+      .accounts(tradeAccounts(platform, tokenA))
+      .rpc();
+  });
+
+  it("rejects the token info of another mint on buy", async () => {
+    await expectFailure(
+      pg.program.methods
+        .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 10))
+        .accounts(
+          tradeAccounts(platform, { mint: tokenA.mint, tokenInfo: tokenB.tokenInfo })
+        )
+        .rpc(),
+      "ConstraintSeeds"
+    );
+  });
+
+  it("rejects the token info of another mint on sell", async () => {
+    await expectFailure(
+      pg.program.methods
+        .sellTokens(new anchor.BN(1_000_000))
+        .accounts(
+          tradeAccounts(platform, { mint: tokenA.mint, tokenInfo: tokenB.tokenInfo })
+        )
+        .rpc(),
+      "ConstraintSeeds"
+    );
+  });
+
+  it("rejects the legacy name derived token info address", async () => {
+    await expectFailure(
+      pg.program.methods
+        .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 10))
+        .accounts(
+          tradeAccounts(platform, {
+            mint: tokenA.mint,
+            tokenInfo: findPda([Buffer.from("token"), Buffer.from(tokenA.name)]),
+          })
+        )
+        .rpc(),
+      "AccountNotInitialized"
+    );
+  });
+
+  it("rejects a token info owned by another program", async () => {
+    await expectFailure(
+      pg.program.methods
+        .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 10))
+        .accounts(
+          tradeAccounts(platform, { mint: tokenA.mint, tokenInfo: tokenA.mint })
+        )
+        .rpc(),
+      "AccountOwnedByWrongProgram"
+    );
+  });
+
+  it("rejects the legacy name derived token info address on resume", async () => {
+    await expectFailure(
+      pg.program.methods
+        .resumeToken()
+        .accounts({
+          platform: platform.address,
+          signer: pg.wallet.publicKey,
+          tokenInfo: findPda([Buffer.from("token"), Buffer.from(tokenB.name)]),
+        })
+        .rpc(),
+      "AccountNotInitialized"
+    );
+  });
+});
@@ -0,0 +1,90 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  createToken,
+  expectFailure,
+  findPda,
+  initializePlatform,
+  tokenAccounts,
+  tokenName,
+} from "./fixtures";
+
+const profileParams = (params = {}) => ({
+  description: "A token launched from the tests",
+  website: "https://example.com",
+  twitter: "https://x.com/example",
+  telegram: "https://t.me/example",
+  contentHash: [...Buffer.from(anchor.utils.sha256.hash("token.json"), "hex")],
+  ...params,
+});
+
+const tokenProfile = ({ mint }) =>
+  findPda([Buffer.from("token_profile"), mint.toBuffer()]);
+
+describe("Token profiles", () => {
+  let platform;
+  let token;
+
+  const updateProfile = (params, signer = null) =>
+    pg.program.methods
+      .updateTokenProfile(profileParams(params))
+      .accounts({
+        signer: signer?.publicKey ?? pg.wallet.publicKey,
+        tokenInfo: token.tokenInfo,
+        tokenProfile: tokenProfile(token),
+      })
+      .signers(signer ? [signer] : [])
+      .rpc();
+
+  before(async () => {
+    platform = await initializePlatform();
+
+    const name = tokenName("profile", platform);
+    token = await createToken(
+      platform,
+      name,
+      { profile: profileParams() },
+      { tokenProfile: tokenProfile(tokenAccounts(platform, name)) }
+    );
+  });
+
+  it("publishes the profile along with the token", async () => {
+    const profile = await pg.program.account.tokenProfile.fetch(tokenProfile(token));
+
+    assert(profile.token.equals(token.mint));
+    assert.equal(profile.description, profileParams().description);
+    assert.equal(profile.website, profileParams().website);
+  });
+
+  it("rejects a profile account without profile params", async () => {
+    const name = tokenName("noprofile", platform);
+
+    await expectFailure(
+      createToken(
+        platform,
+        name,
+        {},
+        { tokenProfile: tokenProfile(tokenAccounts(platform, name)) }
+      ),
+      "TokenProfileMismatch"
+    );
+  });
+
+  it("lets the creator update the profile", async () => {
+    await updateProfile({ website: "https://example.org" });
+
+    const profile = await pg.program.account.tokenProfile.fetch(tokenProfile(token));
+    assert.equal(profile.website, "https://example.org");
+  });
+
+  it("rejects fields over their maximum length", async () => {
+    await expectFailure(
+      updateProfile({ description: "x".repeat(257) }),
+      "TokenProfileFieldTooLong"
+    );
+  });
+
+  it("rejects updates from anyone but the creator", async () => {
+    await expectFailure(updateProfile({}, web3.Keypair.generate()), "NotCreator");
+  });
+});
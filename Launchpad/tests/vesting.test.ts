@@ -0,0 +1,177 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  TOKEN_PROGRAM_ID,
+  createToken,
+  expectFailure,
+  findPda,
+  initializePlatform,
+  sleep,
+  tokenAccounts,
+  tokenName,
+  tradeAccounts,
+} from "./fixtures";
+
+const vestingAccounts = ({ mint }) => ({
+  vesting: findPda([Buffer.from("vesting"), mint.toBuffer()]),
+  vestingTokenAccount: findPda([Buffer.from("vesting_account"), mint.toBuffer()]),
+});
+
+describe("Creator allocation vesting", () => {
+  const cliff = 2;
+  const duration = 8;
+  // Curves graduate after this much is bought, fees are off so a single buy lands on it
+  const graduationAmount = new anchor.BN(web3.LAMPORTS_PER_SOL / 10);
+  let platform;
+  let token;
+  let vesting;
+  let bought;
+
+  const claimAccounts = (claimed = token) => ({
+    signer: pg.wallet.publicKey,
+    mint: claimed.mint,
+    tokenInfo: claimed.tokenInfo,
+    ...vestingAccounts(claimed),
+    userTokenAccount: anchor.utils.token.associatedAddress({
+      mint: claimed.mint,
+      owner: pg.wallet.publicKey,
+    }),
+    tokenProgram: TOKEN_PROGRAM_ID,
+  });
+
+  const balance = async () =>
+    new anchor.BN(
+      (
+        await pg.connection.getTokenAccountBalance(claimAccounts().userTokenAccount)
+      ).value.amount
+    );
+
+  const claimedBalance = async () => (await balance()).sub(bought);
+
+  before(async () => {
+    platform = await initializePlatform({
+      feeInBps: new anchor.BN(0),
+      targetPoolBalance: new anchor.BN(30 * web3.LAMPORTS_PER_SOL).add(graduationAmount),
+    });
+
+    // 10% of the supply vests to the creator
+    const name = tokenName("vest", platform);
+    token = await createToken(
+      platform,
+      name,
+      {
+        creatorAllocationBps: new anchor.BN(1_000),
+        vestingCliff: new anchor.BN(cliff),
+        vestingDuration: new anchor.BN(duration),
+      },
+      vestingAccounts(tokenAccounts(platform, name))
+    );
+    vesting = vestingAccounts(token).vesting;
+
+    // Graduate the curve, claims are locked until then
+    await pg.program.methods
+      .buyTokens(graduationAmount)
+      .accounts(tradeAccounts(platform, token))
+      .rpc();
+    assert((await pg.program.account.tokenInfo.fetch(token.tokenInfo)).launched);
+    bought = await balance();
+  });
+
+  it("locks the creator allocation in the vesting escrow", async () => {
+    const tokenInfo = await pg.program.account.tokenInfo.fetch(token.tokenInfo);
+    const vestingAccount = await pg.program.account.vesting.fetch(vesting);
+    const allocation = tokenInfo.totalSupply.muln(1_000).divn(10_000);
+
+    assert(tokenInfo.creatorAllocation.eq(allocation));
+    assert(tokenInfo.tokenReserve.eq(tokenInfo.totalSupply.sub(allocation)));
+    assert(vestingAccount.totalAmount.eq(allocation));
+    assert(vestingAccount.beneficiary.equals(pg.wallet.publicKey));
+    assert(vestingAccount.cliffTime.sub(vestingAccount.startTime).eqn(cliff));
+    assert(vestingAccount.endTime.sub(vestingAccount.startTime).eqn(duration));
+
+    const escrow = await pg.connection.getTokenAccountBalance(
+      vestingAccounts(token).vestingTokenAccount
+    );
+    assert(new anchor.BN(escrow.value.amount).eq(allocation));
+  });
+
+  it("rejects claims before the curve graduates", async () => {
+    const name = tokenName("ungraduated", platform);
+    const ungraduated = await createToken(
+      platform,
+      name,
+      {
+        creatorAllocationBps: new anchor.BN(1_000),
+        vestingCliff: new anchor.BN(0),
+        vestingDuration: new anchor.BN(duration),
+      },
+      vestingAccounts(tokenAccounts(platform, name))
+    );
+    await pg.program.methods
+      .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL / 100))
+      .accounts(tradeAccounts(platform, ungraduated))
+      .rpc();
+
+    // Past the cliff, but selling the allocation would drain the reserve of the buyers
+    await sleep(2);
+    await expectFailure(
+      pg.program.methods.claimVested().accounts(claimAccounts(ungraduated)).rpc(),
+      "NotLaunched"
+    );
+  });
+
+  it("rejects claims before the cliff", async () => {
+    await expectFailure(
+      pg.program.methods.claimVested().accounts(claimAccounts()).rpc(),
+      "NothingToClaim"
+    );
+  });
+
+  it("releases the allocation linearly after the cliff", async () => {
+    await sleep(cliff + 2);
+    await pg.program.methods.claimVested().accounts(claimAccounts()).rpc();
+
+    const vestingAccount = await pg.program.account.vesting.fetch(vesting);
+    const claimed = await claimedBalance();
+    assert(claimed.gtn(0));
+    assert(claimed.lt(vestingAccount.totalAmount));
+    assert(vestingAccount.claimedAmount.eq(claimed));
+
+    // Everything is released once the schedule ends
+    await sleep(duration);
+    await pg.program.methods.claimVested().accounts(claimAccounts()).rpc();
+
+    assert((await claimedBalance()).eq(vestingAccount.totalAmount));
+    await expectFailure(
+      pg.program.methods.claimVested().accounts(claimAccounts()).rpc(),
+      "NothingToClaim"
+    );
+  });
+
+  it("rejects vesting accounts without a creator allocation", async () => {
+    const name = tokenName("novest", platform);
+
+    await expectFailure(
+      createToken(platform, name, {}, vestingAccounts(tokenAccounts(platform, name))),
+      "UnexpectedVestingAccounts"
+    );
+  });
+
+  it("rejects a vesting schedule ending past the clock range", async () => {
+    const name = tokenName("overflow", platform);
+
+    await expectFailure(
+      createToken(
+        platform,
+        name,
+        {
+          creatorAllocationBps: new anchor.BN(1_000),
+          vestingCliff: new anchor.BN(0),
+          vestingDuration: new anchor.BN("9223372036854775807"),
+        },
+        vestingAccounts(tokenAccounts(platform, name))
+      ),
+      "InvalidVestingSchedule"
+    );
+  });
+});
@@ -0,0 +1,74 @@
+// Only the shared fixtures are imported: web3, anchor, pg and more are globally available
+
+import {
+  createToken,
+  expectFailure,
+  initializePlatform,
+  tokenName,
+  tradeAccounts,
+} from "./fixtures";
+
+describe("Fee withdrawals", () => {
+  const treasury = web3.Keypair.generate().publicKey;
+  let platform;
+
+  const withdrawFees = (amount, accounts = {}) =>
+    pg.program.methods
+      .withdrawFees(amount)
+      .accounts({
+        platform: platform.address,
+        signer: pg.wallet.publicKey,
+        treasury,
+        systemProgram: web3.SystemProgram.programId,
+        ...accounts,
+      })
+      .rpc();
+
+  before(async () => {
+    platform = await initializePlatform({ treasury });
+
+    // Accrue some trading fees to withdraw
+    const token = await createToken(platform, tokenName("fees", platform));
+    await pg.program.methods
+      .buyTokens(new anchor.BN(web3.LAMPORTS_PER_SOL))
+      .accounts(tradeAccounts(platform, token))
+      .rpc();
+  });
+
+  it("withdraws part of the fees to the treasury", async () => {
+    const { accumulatedFees } = await pg.program.account.platform.fetch(platform.address);
+    const amount = accumulatedFees.divn(2);
+
+    await withdrawFees(amount);
+
+    assert.equal(await pg.connection.getBalance(treasury), amount.toNumber());
+    const platformAccount = await pg.program.account.platform.fetch(platform.address);
+    assert(platformAccount.accumulatedFees.eq(accumulatedFees.sub(amount)));
+  });
+
+  it("rejects withdrawing more than the accumulated fees", async () => {
+    const { accumulatedFees } = await pg.program.account.platform.fetch(platform.address);
+
+    await expectFailure(withdrawFees(accumulatedFees.addn(1)), "InsufficientFees");
+  });
+
+  it("rejects a destination other than the treasury", async () => {
+    await expectFailure(
+      withdrawFees(null, { treasury: pg.wallet.publicKey }),
+      "InvalidTreasury"
+    );
+  });
+
+  it("withdraws the rest of the fees and keeps the platform rent exempt", async () => {
+    await withdrawFees(null);
+
+    const platformAccount = await pg.program.account.platform.fetch(platform.address);
+    assert(platformAccount.accumulatedFees.isZero());
+
+    const account = await pg.connection.getAccountInfo(platform.address);
+    assert(
+      account.lamports >=
+        (await pg.connection.getMinimumBalanceForRentExemption(account.data.length))
+    );
+  });
+});